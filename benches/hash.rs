@@ -4,7 +4,7 @@ use derive_hash_fast::*;
 use std::hint::black_box;
 use std::hash::{DefaultHasher, Hash, Hasher};
 
-criterion_group!(benches, bench_compound_struct_64, bench_compound_struct_80, bench_compound_struct_128, bench_compound_struct_160, bench_slice_of_compound_structs, bench_slice_of_u8_newtype, bench_short_slice_of_u8_newtype);
+criterion_group!(benches, bench_compound_struct_64, bench_compound_struct_80, bench_compound_struct_128, bench_compound_struct_160, bench_compound_struct_768, bench_compound_struct_2048, bench_compound_struct_4096, bench_slice_of_compound_structs, bench_slice_of_u8_newtype, bench_short_slice_of_u8_newtype, bench_write_plans, bench_tail_strategies, bench_coalescing);
 criterion_main!(benches);
 
 fn hash_it(value: impl Hash, mut hasher: impl Hasher) -> u64 {
@@ -19,6 +19,8 @@ pub fn bench_compound_struct_64(c: &mut Criterion) {
     bench_compound_struct_64_with_hasher(c, rapidhash::RapidHasher::default(), "rapidhash::RapidHasher");
     bench_compound_struct_64_with_hasher(c, ahash::AHasher::default(), "ahash::AHasher");
     bench_compound_struct_64_with_hasher(c, xxhash_rust::xxh3::Xxh3Default::default(), "xxh3::Xxh3Default");
+    #[cfg(feature = "fixed-hasher")]
+    bench_compound_struct_64_with_hasher(c, FixedHasher::default(), "derive_hash_fast::FixedHasher");
 }
 
 pub fn bench_compound_struct_64_with_hasher(c: &mut Criterion, hasher: impl Hasher + Clone, hasher_name: &str) {
@@ -38,6 +40,8 @@ pub fn bench_compound_struct_80(c: &mut Criterion) {
     bench_compound_struct_80_with_hasher(c, rapidhash::RapidHasher::default(), "rapidhash::RapidHasher");
     bench_compound_struct_80_with_hasher(c, ahash::AHasher::default(), "ahash::AHasher");
     bench_compound_struct_80_with_hasher(c, xxhash_rust::xxh3::Xxh3Default::default(), "xxh3::Xxh3Default");
+    #[cfg(feature = "fixed-hasher")]
+    bench_compound_struct_80_with_hasher(c, FixedHasher::default(), "derive_hash_fast::FixedHasher");
 }
 
 
@@ -58,6 +62,8 @@ pub fn bench_compound_struct_128(c: &mut Criterion) {
     bench_compound_struct_128_with_hasher(c, rapidhash::RapidHasher::default(), "rapidhash::RapidHasher");
     bench_compound_struct_128_with_hasher(c, ahash::AHasher::default(), "ahash::AHasher");
     bench_compound_struct_128_with_hasher(c, xxhash_rust::xxh3::Xxh3Default::default(), "xxh3::Xxh3Default");
+    #[cfg(feature = "fixed-hasher")]
+    bench_compound_struct_128_with_hasher(c, FixedHasher::default(), "derive_hash_fast::FixedHasher");
 }
 
 
@@ -78,6 +84,8 @@ pub fn bench_compound_struct_160(c: &mut Criterion) {
     bench_compound_struct_160_with_hasher(c, rapidhash::RapidHasher::default(), "rapidhash::RapidHasher");
     bench_compound_struct_160_with_hasher(c, ahash::AHasher::default(), "ahash::AHasher");
     bench_compound_struct_160_with_hasher(c, xxhash_rust::xxh3::Xxh3Default::default(), "xxh3::Xxh3Default");
+    #[cfg(feature = "fixed-hasher")]
+    bench_compound_struct_160_with_hasher(c, FixedHasher::default(), "derive_hash_fast::FixedHasher");
 }
 
 
@@ -92,12 +100,80 @@ pub fn bench_compound_struct_160_with_hasher(c: &mut Criterion, hasher: impl Has
     );
 }
 
+pub fn bench_compound_struct_768(c: &mut Criterion) {
+    bench_compound_struct_768_with_hasher(c, DefaultHasher::default(), "std::hash::DefaultHasher");
+    bench_compound_struct_768_with_hasher(c, rustc_hash::FxHasher::default(), "rustc_hash::FxHasher");
+    bench_compound_struct_768_with_hasher(c, rapidhash::RapidHasher::default(), "rapidhash::RapidHasher");
+    bench_compound_struct_768_with_hasher(c, ahash::AHasher::default(), "ahash::AHasher");
+    bench_compound_struct_768_with_hasher(c, xxhash_rust::xxh3::Xxh3Default::default(), "xxh3::Xxh3Default");
+    #[cfg(feature = "fixed-hasher")]
+    bench_compound_struct_768_with_hasher(c, FixedHasher::default(), "derive_hash_fast::FixedHasher");
+}
+
+
+pub fn bench_compound_struct_768_with_hasher(c: &mut Criterion, hasher: impl Hasher + Clone, hasher_name: &str) {
+    bench_structs_with_hasher(c, 
+        compound768::Derive {a: 1, b: 2, c: 1337, d: 100500, e: [30; 16]}, "Compound 768-bit struct with [derive(Hash)]", 
+        compound768::FastB {a: 1, b: 2, c: 1337, d: 100500, e: [30; 16]}, "Compound 768-bit struct with derive_hash_fast_bytemuck", 
+        compound768::FastZ {a: 1, b: 2, c: 1337, d: 100500, e: [30; 16]}, "Compound 768-bit struct with derive_hash_fast_zerocopy",
+        compound768::ByteHashB {a: 1, b: 2, c: 1337, d: 100500, e: [30; 16]}, "Compound 768-bit struct with bytemuck::ByteHash",
+        compound768::ByteHashZ {a: 1, b: 2, c: 1337, d: 100500, e: [30; 16]}, "Compound 768-bit struct with zerocopy::ByteHash",
+        hasher, hasher_name
+    );
+}
+
+pub fn bench_compound_struct_2048(c: &mut Criterion) {
+    bench_compound_struct_2048_with_hasher(c, DefaultHasher::default(), "std::hash::DefaultHasher");
+    bench_compound_struct_2048_with_hasher(c, rustc_hash::FxHasher::default(), "rustc_hash::FxHasher");
+    bench_compound_struct_2048_with_hasher(c, rapidhash::RapidHasher::default(), "rapidhash::RapidHasher");
+    bench_compound_struct_2048_with_hasher(c, ahash::AHasher::default(), "ahash::AHasher");
+    bench_compound_struct_2048_with_hasher(c, xxhash_rust::xxh3::Xxh3Default::default(), "xxh3::Xxh3Default");
+    #[cfg(feature = "fixed-hasher")]
+    bench_compound_struct_2048_with_hasher(c, FixedHasher::default(), "derive_hash_fast::FixedHasher");
+}
+
+
+pub fn bench_compound_struct_2048_with_hasher(c: &mut Criterion, hasher: impl Hasher + Clone, hasher_name: &str) {
+    bench_structs_with_hasher(c, 
+        compound2048::Derive {a: [1; 16], b: [100500; 16]}, "Compound 2048-bit struct with [derive(Hash)]", 
+        compound2048::FastB {a: [1; 16], b: [100500; 16]}, "Compound 2048-bit struct with derive_hash_fast_bytemuck", 
+        compound2048::FastZ {a: [1; 16], b: [100500; 16]}, "Compound 2048-bit struct with derive_hash_fast_zerocopy",
+        compound2048::ByteHashB {a: [1; 16], b: [100500; 16]}, "Compound 2048-bit struct with bytemuck::ByteHash",
+        compound2048::ByteHashZ {a: [1; 16], b: [100500; 16]}, "Compound 2048-bit struct with zerocopy::ByteHash",
+        hasher, hasher_name
+    );
+}
+
+pub fn bench_compound_struct_4096(c: &mut Criterion) {
+    bench_compound_struct_4096_with_hasher(c, DefaultHasher::default(), "std::hash::DefaultHasher");
+    bench_compound_struct_4096_with_hasher(c, rustc_hash::FxHasher::default(), "rustc_hash::FxHasher");
+    bench_compound_struct_4096_with_hasher(c, rapidhash::RapidHasher::default(), "rapidhash::RapidHasher");
+    bench_compound_struct_4096_with_hasher(c, ahash::AHasher::default(), "ahash::AHasher");
+    bench_compound_struct_4096_with_hasher(c, xxhash_rust::xxh3::Xxh3Default::default(), "xxh3::Xxh3Default");
+    #[cfg(feature = "fixed-hasher")]
+    bench_compound_struct_4096_with_hasher(c, FixedHasher::default(), "derive_hash_fast::FixedHasher");
+}
+
+
+pub fn bench_compound_struct_4096_with_hasher(c: &mut Criterion, hasher: impl Hasher + Clone, hasher_name: &str) {
+    bench_structs_with_hasher(c, 
+        compound4096::Derive {a: [1; 32], b: [100500; 32]}, "Compound 4096-bit struct with [derive(Hash)]", 
+        compound4096::FastB {a: [1; 32], b: [100500; 32]}, "Compound 4096-bit struct with derive_hash_fast_bytemuck", 
+        compound4096::FastZ {a: [1; 32], b: [100500; 32]}, "Compound 4096-bit struct with derive_hash_fast_zerocopy",
+        compound4096::ByteHashB {a: [1; 32], b: [100500; 32]}, "Compound 4096-bit struct with bytemuck::ByteHash",
+        compound4096::ByteHashZ {a: [1; 32], b: [100500; 32]}, "Compound 4096-bit struct with zerocopy::ByteHash",
+        hasher, hasher_name
+    );
+}
+
 pub fn bench_slice_of_compound_structs(c: &mut Criterion) {
     bench_slice_of_compound_structs_with_hasher(c, DefaultHasher::default(), "std::hash::DefaultHasher");
     bench_slice_of_compound_structs_with_hasher(c, rustc_hash::FxHasher::default(), "rustc_hash::FxHasher");
     bench_slice_of_compound_structs_with_hasher(c, rapidhash::RapidHasher::default(), "rapidhash::RapidHasher");
     bench_slice_of_compound_structs_with_hasher(c, ahash::AHasher::default(), "ahash::AHasher");
     bench_slice_of_compound_structs_with_hasher(c, xxhash_rust::xxh3::Xxh3Default::default(), "xxh3::Xxh3Default");
+    #[cfg(feature = "fixed-hasher")]
+    bench_slice_of_compound_structs_with_hasher(c, FixedHasher::default(), "derive_hash_fast::FixedHasher");
 }
 
 pub fn bench_slice_of_compound_structs_with_hasher(c: &mut Criterion, hasher: impl Hasher + Clone, hasher_name: &str) {
@@ -117,6 +193,8 @@ pub fn bench_slice_of_u8_newtype(c: &mut Criterion) {
     bench_slice_of_u8_newtype_with_hasher(c, rapidhash::RapidHasher::default(), "rapidhash::RapidHasher");
     bench_slice_of_u8_newtype_with_hasher(c, ahash::AHasher::default(), "ahash::AHasher");
     bench_slice_of_u8_newtype_with_hasher(c, xxhash_rust::xxh3::Xxh3Default::default(), "xxh3::Xxh3Default");
+    #[cfg(feature = "fixed-hasher")]
+    bench_slice_of_u8_newtype_with_hasher(c, FixedHasher::default(), "derive_hash_fast::FixedHasher");
 }
 
 pub fn bench_slice_of_u8_newtype_with_hasher(c: &mut Criterion, hasher: impl Hasher + Clone, hasher_name: &str) {
@@ -136,6 +214,8 @@ pub fn bench_short_slice_of_u8_newtype(c: &mut Criterion) {
     bench_short_slice_of_u8_newtype_with_hasher(c, rapidhash::RapidHasher::default(), "rapidhash::RapidHasher");
     bench_short_slice_of_u8_newtype_with_hasher(c, ahash::AHasher::default(), "ahash::AHasher");
     bench_short_slice_of_u8_newtype_with_hasher(c, xxhash_rust::xxh3::Xxh3Default::default(), "xxh3::Xxh3Default");
+    #[cfg(feature = "fixed-hasher")]
+    bench_short_slice_of_u8_newtype_with_hasher(c, FixedHasher::default(), "derive_hash_fast::FixedHasher");
 }
 
 pub fn bench_short_slice_of_u8_newtype_with_hasher(c: &mut Criterion, hasher: impl Hasher + Clone, hasher_name: &str) {
@@ -149,6 +229,89 @@ pub fn bench_short_slice_of_u8_newtype_with_hasher(c: &mut Criterion, hasher: im
     );
 }
 
+pub fn bench_write_plans(c: &mut Criterion) {
+    bench_write_plans_with_hasher(c, DefaultHasher::default(), "std::hash::DefaultHasher");
+    bench_write_plans_with_hasher(c, rustc_hash::FxHasher::default(), "rustc_hash::FxHasher");
+    bench_write_plans_with_hasher(c, rapidhash::RapidHasher::default(), "rapidhash::RapidHasher");
+    bench_write_plans_with_hasher(c, ahash::AHasher::default(), "ahash::AHasher");
+    bench_write_plans_with_hasher(c, xxhash_rust::xxh3::Xxh3Default::default(), "xxh3::Xxh3Default");
+    #[cfg(feature = "fixed-hasher")]
+    bench_write_plans_with_hasher(c, FixedHasher::default(), "derive_hash_fast::FixedHasher");
+}
+
+pub fn bench_write_plans_with_hasher(c: &mut Criterion, hasher: impl Hasher + Clone, hasher_name: &str) {
+    bench_structs_with_hasher(c,
+        compound80::Derive {a: true, b: 2, c: 1337, d: 5, e: 69, f: 0xFFF}, "Write plan for 80-bit struct: [derive(Hash)]",
+        compound80::PlanOptimal {a: true, b: 2, c: 1337, d: 5, e: 69, f: 0xFFF}, "Write plan for 80-bit struct: Optimal",
+        compound80::PlanU64Only {a: true, b: 2, c: 1337, d: 5, e: 69, f: 0xFFF}, "Write plan for 80-bit struct: U64Only",
+        compound80::PlanU128Preferred {a: true, b: 2, c: 1337, d: 5, e: 69, f: 0xFFF}, "Write plan for 80-bit struct: U128Preferred",
+        compound80::PlanSingleWrite {a: true, b: 2, c: 1337, d: 5, e: 69, f: 0xFFF}, "Write plan for 80-bit struct: SingleWrite",
+        hasher.clone(), hasher_name
+    );
+    bench_structs_with_hasher(c,
+        compound160::Derive {a: 1, b: 2, c: 1337, d: 100500, e: 30}, "Write plan for 160-bit struct: [derive(Hash)]",
+        compound160::PlanOptimal {a: 1, b: 2, c: 1337, d: 100500, e: 30}, "Write plan for 160-bit struct: Optimal",
+        compound160::PlanU64Only {a: 1, b: 2, c: 1337, d: 100500, e: 30}, "Write plan for 160-bit struct: U64Only",
+        compound160::PlanU128Preferred {a: 1, b: 2, c: 1337, d: 100500, e: 30}, "Write plan for 160-bit struct: U128Preferred",
+        compound160::PlanSingleWrite {a: 1, b: 2, c: 1337, d: 100500, e: 30}, "Write plan for 160-bit struct: SingleWrite",
+        hasher.clone(), hasher_name
+    );
+    bench_structs_with_hasher(c,
+        compound768::Derive {a: 1, b: 2, c: 1337, d: 100500, e: [30; 16]}, "Write plan for 768-bit struct: [derive(Hash)]",
+        compound768::PlanOptimal {a: 1, b: 2, c: 1337, d: 100500, e: [30; 16]}, "Write plan for 768-bit struct: Optimal",
+        compound768::PlanU64Only {a: 1, b: 2, c: 1337, d: 100500, e: [30; 16]}, "Write plan for 768-bit struct: U64Only",
+        compound768::PlanU128Preferred {a: 1, b: 2, c: 1337, d: 100500, e: [30; 16]}, "Write plan for 768-bit struct: U128Preferred",
+        compound768::PlanSingleWrite {a: 1, b: 2, c: 1337, d: 100500, e: [30; 16]}, "Write plan for 768-bit struct: SingleWrite",
+        hasher, hasher_name
+    );
+}
+
+pub fn bench_tail_strategies(c: &mut Criterion) {
+    bench_tail_strategies_with_hasher(c, DefaultHasher::default(), "std::hash::DefaultHasher");
+    bench_tail_strategies_with_hasher(c, rustc_hash::FxHasher::default(), "rustc_hash::FxHasher");
+    bench_tail_strategies_with_hasher(c, rapidhash::RapidHasher::default(), "rapidhash::RapidHasher");
+    bench_tail_strategies_with_hasher(c, ahash::AHasher::default(), "ahash::AHasher");
+    bench_tail_strategies_with_hasher(c, xxhash_rust::xxh3::Xxh3Default::default(), "xxh3::Xxh3Default");
+    #[cfg(feature = "fixed-hasher")]
+    bench_tail_strategies_with_hasher(c, FixedHasher::default(), "derive_hash_fast::FixedHasher");
+}
+
+pub fn bench_tail_strategies_with_hasher(c: &mut Criterion, hasher: impl Hasher + Clone, hasher_name: &str) {
+    let mut group = c.benchmark_group(hasher_name);
+    let padded80 = compound80::PlanOptimal {a: true, b: 2, c: 1337, d: 5, e: 69, f: 0xFFF};
+    let overlapping80 = compound80::PlanOverlappingTail {a: true, b: 2, c: 1337, d: 5, e: 69, f: 0xFFF};
+    let padded160 = compound160::PlanOptimal {a: 1, b: 2, c: 1337, d: 100500, e: 30};
+    let overlapping160 = compound160::PlanOverlappingTail {a: 1, b: 2, c: 1337, d: 100500, e: 30};
+    group.bench_function("Tail strategy for 80-bit struct: zero padding", |b| b.iter(|| hash_it(padded80, hasher.clone())));
+    group.bench_function("Tail strategy for 80-bit struct: overlapping reads", |b| b.iter(|| hash_it(overlapping80, hasher.clone())));
+    group.bench_function("Tail strategy for 160-bit struct: zero padding", |b| b.iter(|| hash_it(padded160, hasher.clone())));
+    group.bench_function("Tail strategy for 160-bit struct: overlapping reads", |b| b.iter(|| hash_it(overlapping160, hasher.clone())));
+    group.finish();
+}
+
+pub fn bench_coalescing(c: &mut Criterion) {
+    bench_coalescing_with_hasher(c, DefaultHasher::default(), "std::hash::DefaultHasher");
+    bench_coalescing_with_hasher(c, rustc_hash::FxHasher::default(), "rustc_hash::FxHasher");
+    bench_coalescing_with_hasher(c, rapidhash::RapidHasher::default(), "rapidhash::RapidHasher");
+    bench_coalescing_with_hasher(c, ahash::AHasher::default(), "ahash::AHasher");
+    bench_coalescing_with_hasher(c, xxhash_rust::xxh3::Xxh3Default::default(), "xxh3::Xxh3Default");
+    #[cfg(feature = "fixed-hasher")]
+    bench_coalescing_with_hasher(c, FixedHasher::default(), "derive_hash_fast::FixedHasher");
+}
+
+pub fn bench_coalescing_with_hasher(c: &mut Criterion, hasher: impl Hasher + Clone, hasher_name: &str) {
+    let mut group = c.benchmark_group(hasher_name);
+    let derive80 = compound80::Derive {a: true, b: 2, c: 1337, d: 5, e: 69, f: 0xFFF};
+    let derive160 = compound160::Derive {a: 1, b: 2, c: 1337, d: 100500, e: 30};
+    let coalescing = CoalescingHasher::new(hasher.clone());
+    group.bench_function("Coalescing 80-bit struct: [derive(Hash)]", |b| b.iter(|| hash_it(derive80.clone(), hasher.clone())));
+    group.bench_function("Coalescing 80-bit struct: [derive(Hash)] with CoalescingHasher", |b| b.iter(|| hash_it(derive80.clone(), coalescing.clone())));
+    group.bench_function("Coalescing 160-bit struct: [derive(Hash)]", |b| b.iter(|| hash_it(derive160.clone(), hasher.clone())));
+    group.bench_function("Coalescing 160-bit struct: [derive(Hash)] with CoalescingHasher", |b| b.iter(|| hash_it(derive160.clone(), coalescing.clone())));
+    group.finish();
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn bench_structs_with_hasher(c: &mut Criterion, 
     struct_1: impl Hash + Clone, struct_1_name: &str,
     struct_2: impl Hash + Clone, struct_2_name: &str,
@@ -203,6 +366,25 @@ macro_rules! generate_structs {
         pub struct ByteHashZ {
             $( pub $field_name: $field_type ),*
         }
+
+        $crate::generate_plan_structs!(PlanOptimal, derive_hash_fast::Optimal, $($field_name: $field_type),*);
+        $crate::generate_plan_structs!(PlanU64Only, derive_hash_fast::U64Only, $($field_name: $field_type),*);
+        $crate::generate_plan_structs!(PlanU128Preferred, derive_hash_fast::U128Preferred, $($field_name: $field_type),*);
+        $crate::generate_plan_structs!(PlanSingleWrite, derive_hash_fast::SingleWrite, $($field_name: $field_type),*);
+        $crate::generate_plan_structs!(PlanOverlappingTail, derive_hash_fast::OverlappingTail, $($field_name: $field_type),*);
+    };
+}
+
+#[macro_export]
+macro_rules! generate_plan_structs {
+    ($name:ident, $plan:ty, $( $field_name:ident : $field_type:ty ),*) => {
+        #[repr(C)]
+        #[derive(Copy, Clone, bytemuck::NoUninit)]
+        pub struct $name {
+            $( pub $field_name: $field_type ),*
+        }
+
+        derive_hash_fast::derive_hash_fast_bytemuck!(plan = $plan, $name);
     };
 }
 
@@ -261,6 +443,42 @@ mod compound160 {
     }
 }
 
+mod compound768 {
+    use super::generate_structs;
+
+    generate_structs! {
+        pub struct Compound768 {
+            a: u64,
+            b: u64,
+            c: u64,
+            d: u64,
+            e: [u32; 16]
+        }
+    }
+}
+
+mod compound2048 {
+    use super::generate_structs;
+
+    generate_structs! {
+        pub struct Compound2048 {
+            a: [u64; 16],
+            b: [u64; 16]
+        }
+    }
+}
+
+mod compound4096 {
+    use super::generate_structs;
+
+    generate_structs! {
+        pub struct Compound4096 {
+            a: [u64; 32],
+            b: [u64; 32]
+        }
+    }
+}
+
 #[derive(Clone, Hash)]
 struct U8NewtypeDerive(u8);
 
@@ -15,6 +15,8 @@ pub fn bench_compound_struct_64(c: &mut Criterion) {
     bench_compound_struct_64_with_hasher(c, rapidhash::RapidHasher::default(), "rapidhash::RapidHasher");
     bench_compound_struct_64_with_hasher(c, ahash::AHasher::default(), "ahash::AHasher");
     bench_compound_struct_64_with_hasher(c, xxhash_rust::xxh3::Xxh3Default::default(), "xxh3::Xxh3Default");
+    #[cfg(feature = "fixed-hasher")]
+    bench_compound_struct_64_with_hasher(c, FixedHasher::default(), "derive_hash_fast::FixedHasher");
 }
 
 pub fn bench_compound_struct_64_with_hasher(c: &mut Criterion, hasher: impl Hasher + Clone + Default, hasher_name: &str) {
@@ -34,6 +36,8 @@ pub fn bench_compound_struct_80(c: &mut Criterion) {
     bench_compound_struct_80_with_hasher(c, rapidhash::RapidHasher::default(), "rapidhash::RapidHasher");
     bench_compound_struct_80_with_hasher(c, ahash::AHasher::default(), "ahash::AHasher");
     bench_compound_struct_80_with_hasher(c, xxhash_rust::xxh3::Xxh3Default::default(), "xxh3::Xxh3Default");
+    #[cfg(feature = "fixed-hasher")]
+    bench_compound_struct_80_with_hasher(c, FixedHasher::default(), "derive_hash_fast::FixedHasher");
 }
 
 
@@ -54,6 +58,8 @@ pub fn bench_compound_struct_128(c: &mut Criterion) {
     bench_compound_struct_128_with_hasher(c, rapidhash::RapidHasher::default(), "rapidhash::RapidHasher");
     bench_compound_struct_128_with_hasher(c, ahash::AHasher::default(), "ahash::AHasher");
     bench_compound_struct_128_with_hasher(c, xxhash_rust::xxh3::Xxh3Default::default(), "xxh3::Xxh3Default");
+    #[cfg(feature = "fixed-hasher")]
+    bench_compound_struct_128_with_hasher(c, FixedHasher::default(), "derive_hash_fast::FixedHasher");
 }
 
 
@@ -74,6 +80,8 @@ pub fn bench_compound_struct_160(c: &mut Criterion) {
     bench_compound_struct_160_with_hasher(c, rapidhash::RapidHasher::default(), "rapidhash::RapidHasher");
     bench_compound_struct_160_with_hasher(c, ahash::AHasher::default(), "ahash::AHasher");
     bench_compound_struct_160_with_hasher(c, xxhash_rust::xxh3::Xxh3Default::default(), "xxh3::Xxh3Default");
+    #[cfg(feature = "fixed-hasher")]
+    bench_compound_struct_160_with_hasher(c, FixedHasher::default(), "derive_hash_fast::FixedHasher");
 }
 
 
@@ -88,12 +96,13 @@ pub fn bench_compound_struct_160_with_hasher(c: &mut Criterion, hasher: impl Has
     );
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn bench_structs_with_hasher(c: &mut Criterion, 
-    struct_1: impl Hash + Eq + PartialEq + Clone + for<'a> Arbitrary<'a>, struct_1_name: &str,
-    struct_2: impl Hash + Eq + PartialEq + Clone + for<'a> Arbitrary<'a>, struct_2_name: &str,
-    struct_3: impl Hash + Eq + PartialEq + Clone + for<'a> Arbitrary<'a>, struct_3_name: &str,
-    struct_4: impl Hash + Eq + PartialEq + Clone + for<'a> Arbitrary<'a>, struct_4_name: &str,
-    struct_5: impl Hash + Eq + PartialEq + Clone + for<'a> Arbitrary<'a>, struct_5_name: &str,
+    struct_1: impl Hash + Eq + Clone + for<'a> Arbitrary<'a>, struct_1_name: &str,
+    struct_2: impl Hash + Eq + Clone + for<'a> Arbitrary<'a>, struct_2_name: &str,
+    struct_3: impl Hash + Eq + Clone + for<'a> Arbitrary<'a>, struct_3_name: &str,
+    struct_4: impl Hash + Eq + Clone + for<'a> Arbitrary<'a>, struct_4_name: &str,
+    struct_5: impl Hash + Eq + Clone + for<'a> Arbitrary<'a>, struct_5_name: &str,
     mut hasher: impl Hasher + Clone + Default, hasher_name: &str
 ) {
     let mut group = c.benchmark_group(hasher_name);
@@ -105,7 +114,7 @@ pub fn bench_structs_with_hasher(c: &mut Criterion,
     group.finish();
 }
 
-pub fn bench_struct<S: Hash + Eq + PartialEq + Clone + for<'a> Arbitrary<'a>, H: Hasher + Default>(b: &mut Bencher, struct_to_bench: &S, mut hasher: &H) {
+pub fn bench_struct<S: Hash + Eq + Clone + for<'a> Arbitrary<'a>, H: Hasher + Default>(b: &mut Bencher, struct_to_bench: &S, mut hasher: &H) {
     let mut data = vec![0; 4096];
     fastrand::fill(&mut data);
     let mut unstructured = Unstructured::new(&data);
@@ -116,7 +125,7 @@ pub fn bench_struct<S: Hash + Eq + PartialEq + Clone + for<'a> Arbitrary<'a>, H:
 
     b.iter(|| {
         let mut set: HashSet<S, BuildHasherDefault<H>> = HashSet::default();
-        let mut structs_to_insert = (&structs[..structs.len() / 2]).to_vec();
+        let mut structs_to_insert = structs[..structs.len() / 2].to_vec();
 
         // insert half the structs into the set, triggering reallocations and rehashing
         for s in structs_to_insert.into_iter() {
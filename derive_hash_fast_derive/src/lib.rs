@@ -0,0 +1,111 @@
+//! `#[derive(HashFast)]`, a derive macro front-end for the
+//! [`derive_hash_fast`](https://docs.rs/derive_hash_fast) crate.
+//!
+//! Don't depend on this crate directly. Enable the `derive` feature of
+//! `derive_hash_fast` instead, which re-exports the macro as `derive_hash_fast::HashFast`.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, Error, Ident};
+
+/// Derives a fast `Hash` implementation.
+///
+/// The backend used to view the type as bytes must be selected with
+/// `#[hash_fast(zerocopy)]` or `#[hash_fast(bytemuck)]`.
+/// The generated implementation is identical to the one produced by
+/// `derive_hash_fast_zerocopy!` or `derive_hash_fast_bytemuck!` respectively.
+///
+/// A write plan other than the default can be selected with `plan`,
+/// e.g. `#[hash_fast(zerocopy, plan = derive_hash_fast::U64Only)]`.
+#[proc_macro_derive(HashFast, attributes(hash_fast))]
+pub fn derive_hash_fast(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let (backend, bounds, plan) = backend_macro(input)?;
+    let plan = plan.map(|plan| quote!(plan = #plan,));
+    let name = &input.ident;
+    if input.generics.params.is_empty() {
+        return Ok(quote! {
+            ::derive_hash_fast::#backend!(#plan #name);
+        });
+    }
+    // Generic types need the backend's traits for every instantiation
+    let mut generics = input.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!(Self: #bounds));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        ::derive_hash_fast::#backend!(#plan impl #impl_generics for #name #ty_generics #where_clause);
+    })
+}
+
+/// Maps the `#[hash_fast(...)]` attribute to the `macro_rules!` macro implementing that backend,
+/// the trait bounds that backend requires, and the write plan if one is selected.
+fn backend_macro(input: &DeriveInput) -> syn::Result<(Ident, proc_macro2::TokenStream, Option<syn::Type>)> {
+    let mut backend = None;
+    let mut plan = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("hash_fast")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("plan") {
+                if plan.is_some() {
+                    return Err(meta.error("the plan is already specified"));
+                }
+                plan = Some(meta.value()?.parse::<syn::Type>()?);
+                return Ok(());
+            }
+            let (name, bounds) = if meta.path.is_ident("zerocopy") {
+                let zerocopy = zerocopy_path();
+                (
+                    "derive_hash_fast_zerocopy",
+                    quote!(#zerocopy::IntoBytes + #zerocopy::Immutable),
+                )
+            } else if meta.path.is_ident("bytemuck") {
+                let bytemuck = bytemuck_path();
+                ("derive_hash_fast_bytemuck", quote!(#bytemuck::NoUninit))
+            } else {
+                return Err(meta.error("expected `zerocopy`, `bytemuck` or `plan = ...`"));
+            };
+            if backend.is_some() {
+                return Err(meta.error("the backend is already specified"));
+            }
+            backend = Some((Ident::new(name, Span::call_site()), bounds));
+            Ok(())
+        })?;
+    }
+    let (backend, bounds) = backend.ok_or_else(|| {
+        Error::new(
+            Span::call_site(),
+            "#[derive(HashFast)] requires selecting a backend: \
+             add #[hash_fast(zerocopy)] or #[hash_fast(bytemuck)]",
+        )
+    })?;
+    Ok((backend, bounds, plan))
+}
+
+/// The path to `bytemuck` in the generated bounds. Matches the `__bytemuck!` macro
+/// of `derive_hash_fast`, which enables the feature of the same name on this crate.
+fn bytemuck_path() -> proc_macro2::TokenStream {
+    if cfg!(feature = "bytemuck") {
+        quote!(::derive_hash_fast::__private::bytemuck)
+    } else {
+        quote!(::bytemuck)
+    }
+}
+
+/// The path to `zerocopy` in the generated bounds, see [`bytemuck_path`].
+fn zerocopy_path() -> proc_macro2::TokenStream {
+    if cfg!(feature = "zerocopy") {
+        quote!(::derive_hash_fast::__private::zerocopy)
+    } else {
+        quote!(::zerocopy)
+    }
+}
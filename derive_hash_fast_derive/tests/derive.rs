@@ -0,0 +1,101 @@
+use derive_hash_fast::HashFast;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+fn hash_of(val: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    val.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[allow(dead_code)] // only read through the byte view
+#[derive(zerocopy::Immutable, zerocopy::IntoBytes, HashFast)]
+#[hash_fast(zerocopy)]
+struct DerivedZ {
+    a: u16,
+    b: u16,
+    c: char,
+}
+
+#[allow(dead_code)] // only read through the byte view
+#[derive(zerocopy::Immutable, zerocopy::IntoBytes)]
+struct MacroZ {
+    a: u16,
+    b: u16,
+    c: char,
+}
+
+derive_hash_fast::derive_hash_fast_zerocopy!(MacroZ);
+
+#[test]
+fn test_zerocopy_matches_macro() {
+    let derived = DerivedZ { a: 5, b: 10, c: 'a' };
+    let via_macro = MacroZ { a: 5, b: 10, c: 'a' };
+    assert_eq!(hash_of(&derived), hash_of(&via_macro));
+    assert_ne!(hash_of(&derived), hash_of(&DerivedZ { a: 5, b: 500, c: 'a' }));
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::NoUninit, HashFast)]
+#[hash_fast(bytemuck)]
+struct DerivedB {
+    a: u16,
+    b: u16,
+    c: char,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::NoUninit)]
+struct MacroB {
+    a: u16,
+    b: u16,
+    c: char,
+}
+
+derive_hash_fast::derive_hash_fast_bytemuck!(MacroB);
+
+#[test]
+fn test_bytemuck_matches_macro() {
+    let derived = [DerivedB { a: 5, b: 10, c: 'a' }; 3];
+    let via_macro = [MacroB { a: 5, b: 10, c: 'a' }; 3];
+    assert_eq!(hash_of(&derived), hash_of(&via_macro));
+    assert_eq!(hash_of(&derived[0]), hash_of(&via_macro[0]));
+}
+
+#[repr(C)]
+#[derive(zerocopy::Immutable, zerocopy::IntoBytes, HashFast)]
+#[hash_fast(zerocopy)]
+struct DerivedGeneric<T, const N: usize> {
+    cells: [T; N],
+}
+
+#[test]
+fn test_generic() {
+    let a = DerivedGeneric { cells: [1u16, 2, 3] };
+    let b = DerivedGeneric { cells: [1u16, 2, 4] };
+    assert_eq!(hash_of(&a), hash_of(&DerivedGeneric { cells: [1u16, 2, 3] }));
+    assert_ne!(hash_of(&a), hash_of(&b));
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::NoUninit, HashFast)]
+#[hash_fast(bytemuck, plan = derive_hash_fast::U64Only)]
+struct DerivedPlanned {
+    a: u64,
+    b: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::NoUninit)]
+struct MacroPlanned {
+    a: u64,
+    b: u64,
+}
+
+derive_hash_fast::derive_hash_fast_bytemuck!(plan = derive_hash_fast::U64Only, MacroPlanned);
+
+#[test]
+fn test_plan_matches_macro() {
+    let derived = DerivedPlanned { a: 1, b: 2 };
+    let via_macro = MacroPlanned { a: 1, b: 2 };
+    assert_eq!(hash_of(&derived), hash_of(&via_macro));
+}
@@ -0,0 +1,140 @@
+use core::hash::{Hash, Hasher};
+use core::ops::{Deref, DerefMut};
+
+use crate::byte_order::NativeEndian;
+use crate::{AsHashBytes, Optimal, WritePlan};
+
+/// A fixed-size array that hashes all of its elements at once, without a length prefix.
+///
+/// `Hash` for `[T; N]` goes through the implementation for slices, which writes the length
+/// and then the elements with a variable-length write, even though `N` is known at compile time.
+/// `FastArray` hashes the `N * T::SIZE` bytes of its elements with the same fixed sequence of
+/// primitive writes as [`hash_fixed_bytes`](crate::hash_fixed_bytes), e.g. two `write_u128` calls for a `[u8; 32]`.
+///
+/// The elements must implement [`AsHashBytes`], which is implemented for integers, `bool`, `char`,
+/// [`OrderedF32`](crate::OrderedF32), [`OrderedF64`](crate::OrderedF64) and every type
+/// passed to [`derive_hash_fast!`](crate::derive_hash_fast).
+///
+/// It dereferences to the array and converts to and from it, so it can replace arrays in map keys.
+/// It does not implement `Borrow<[T; N]>`: the array hashes differently, so looking up a `FastArray` key
+/// through a `&[T; N]` would never find it. Use [`FastArray::from_ref`] to look up keys without copying.
+///
+/// # Examples
+///
+/// ```
+/// use derive_hash_fast::FastArray;
+/// use std::collections::HashMap;
+///
+/// let mut blobs = HashMap::new();
+/// let digest = [7u8; 32];
+/// blobs.insert(FastArray::from(digest), "blob");
+/// assert_eq!(blobs.get(FastArray::from_ref(&digest)), Some(&"blob"));
+/// ```
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FastArray<T, const N: usize>(pub [T; N]);
+
+impl<T, const N: usize> FastArray<T, N> {
+    /// Wraps an array.
+    #[inline]
+    pub const fn new(array: [T; N]) -> Self {
+        Self(array)
+    }
+
+    /// Returns the wrapped array.
+    #[inline]
+    pub fn into_inner(self) -> [T; N] {
+        self.0
+    }
+
+    /// Views a reference to an array as a reference to a `FastArray`, without copying it.
+    #[inline]
+    pub const fn from_ref(array: &[T; N]) -> &Self {
+        // SAFETY: `FastArray` is `repr(transparent)` over `[T; N]`
+        unsafe { &*(array as *const [T; N]).cast::<Self>() }
+    }
+
+    /// Views a slice of arrays as a slice of `FastArray`s, without copying it.
+    #[inline]
+    pub const fn from_slice(arrays: &[[T; N]]) -> &[Self] {
+        // SAFETY: `FastArray` is `repr(transparent)` over `[T; N]`
+        unsafe { core::slice::from_raw_parts(arrays.as_ptr().cast::<Self>(), arrays.len()) }
+    }
+}
+
+impl<T: AsHashBytes, const N: usize> Hash for FastArray<T, N> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let bytes = T::slice_bytes(&self.0);
+        assert!(bytes.len() == T::SIZE * N);
+        Optimal::write::<NativeEndian>(bytes, state)
+    }
+
+    #[inline]
+    fn hash_slice<H: Hasher>(data: &[Self], state: &mut H) {
+        // SAFETY: `FastArray` is `repr(transparent)` over `[T; N]`, which has the layout of `N` elements of `T`
+        let items = unsafe { core::slice::from_raw_parts(data.as_ptr().cast::<T>(), data.len() * N) };
+        crate::hash_slice_bytes(T::slice_bytes(items), state)
+    }
+}
+
+impl<T, const N: usize> Default for FastArray<T, N>
+where
+    [T; N]: Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+impl<T, const N: usize> Deref for FastArray<T, N> {
+    type Target = [T; N];
+
+    #[inline]
+    fn deref(&self) -> &[T; N] {
+        &self.0
+    }
+}
+
+impl<T, const N: usize> DerefMut for FastArray<T, N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T; N] {
+        &mut self.0
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for FastArray<T, N> {
+    #[inline]
+    fn from(array: [T; N]) -> Self {
+        Self(array)
+    }
+}
+
+impl<T, const N: usize> From<FastArray<T, N>> for [T; N] {
+    #[inline]
+    fn from(array: FastArray<T, N>) -> Self {
+        array.0
+    }
+}
+
+impl<T, const N: usize> AsRef<[T; N]> for FastArray<T, N> {
+    #[inline]
+    fn as_ref(&self) -> &[T; N] {
+        &self.0
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for FastArray<T, N> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T, const N: usize> AsMut<[T; N]> for FastArray<T, N> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [T; N] {
+        &mut self.0
+    }
+}
@@ -0,0 +1,347 @@
+use core::hash::Hasher;
+
+use crate::byte_order::{LittleEndian, NativeEndian};
+use crate::WritePlan;
+
+/// Types that can be viewed as a fixed number of bytes for hashing.
+///
+/// This is the extension point behind [`derive_hash_fast!`](crate::derive_hash_fast).
+/// `derive_hash_fast!(zerocopy, MyType)` and `derive_hash_fast!(bytemuck, MyType)` implement it
+/// using the respective crate. Implement it yourself to hash types through a different byte view,
+/// then call `derive_hash_fast!(MyType)`.
+///
+/// It is implemented for integers, `bool` and `char`, but not for floats, whose equality
+/// doesn't match their bytes. There are no blanket implementations for `bytemuck` and `zerocopy` types,
+/// because a type can implement the traits of both crates and the implementations would overlap.
+///
+/// # Safety
+///
+/// - [`AsHashBytes::hash_bytes`] must return exactly [`AsHashBytes::SIZE`] bytes.
+/// - [`AsHashBytes::slice_bytes`] must return the result of [`AsHashBytes::hash_bytes`]
+///   for every element of the slice concatenated together, `s.len() * SIZE` bytes in total.
+/// - Two values that compare equal must produce the same bytes.
+///
+/// # Examples
+///
+/// ```
+/// use derive_hash_fast::{derive_hash_fast, AsHashBytes};
+///
+/// #[repr(transparent)]
+/// #[derive(Eq, PartialEq)]
+/// struct Rgba([u8; 4]);
+///
+/// // SAFETY: `Rgba` is `repr(transparent)` over `[u8; 4]`, which has no padding
+/// unsafe impl AsHashBytes for Rgba {
+///     const SIZE: usize = 4;
+///
+///     fn hash_bytes(&self) -> &[u8] {
+///         &self.0
+///     }
+///
+///     fn slice_bytes(s: &[Self]) -> &[u8] {
+///         unsafe { core::slice::from_raw_parts(s.as_ptr().cast(), s.len() * Self::SIZE) }
+///     }
+/// }
+///
+/// derive_hash_fast!(Rgba);
+///
+/// let mut hashset = std::collections::HashSet::new();
+/// hashset.insert(Rgba([1, 2, 3, 4]));
+/// assert!(hashset.contains(&Rgba([1, 2, 3, 4])));
+/// ```
+pub unsafe trait AsHashBytes: Sized {
+    /// The number of bytes returned by [`AsHashBytes::hash_bytes`].
+    const SIZE: usize;
+
+    /// Returns the bytes of `self` to be hashed.
+    fn hash_bytes(&self) -> &[u8];
+
+    /// Returns the bytes of every element of `s` to be hashed, concatenated together.
+    fn slice_bytes(s: &[Self]) -> &[u8];
+}
+
+macro_rules! impl_as_hash_bytes_for_primitives {
+    ($($T:ty)*) => {
+        $(
+            // SAFETY: primitives have no padding, and equal values have equal bytes
+            unsafe impl AsHashBytes for $T {
+                const SIZE: usize = core::mem::size_of::<$T>();
+
+                #[inline]
+                fn hash_bytes(&self) -> &[u8] {
+                    unsafe { core::slice::from_raw_parts((self as *const Self).cast::<u8>(), core::mem::size_of::<Self>()) }
+                }
+
+                #[inline]
+                fn slice_bytes(s: &[Self]) -> &[u8] {
+                    unsafe { core::slice::from_raw_parts(s.as_ptr().cast::<u8>(), core::mem::size_of_val(s)) }
+                }
+            }
+        )*
+    };
+}
+
+impl_as_hash_bytes_for_primitives!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize bool char);
+
+/// Derives a fast `Hash` implementation for any [`AsHashBytes`] type.
+///
+/// - `derive_hash_fast!(zerocopy, MyType)` implements [`AsHashBytes`] through `zerocopy::IntoBytes`,
+///   and is equivalent to [`derive_hash_fast_zerocopy!`](crate::derive_hash_fast_zerocopy).
+/// - `derive_hash_fast!(bytemuck, MyType)` implements [`AsHashBytes`] through `bytemuck::NoUninit`,
+///   and is equivalent to [`derive_hash_fast_bytemuck!`](crate::derive_hash_fast_bytemuck).
+/// - `derive_hash_fast!(MyType)` uses an existing [`AsHashBytes`] implementation,
+///   which lets you plug in your own way of viewing a type as bytes.
+///
+/// Generic types are supported with the `impl<...> for Type` syntax in all three forms,
+/// e.g. `derive_hash_fast!(zerocopy, impl<T: IntoBytes + Immutable> for Wrapper<T>)`.
+///
+/// Prefixing any form with `portable,`, e.g. `derive_hash_fast!(portable, zerocopy, MyType)`,
+/// makes the hash independent of the endianness of the target, see
+/// [`hash_fixed_bytes_portable`](crate::hash_fixed_bytes_portable). The type must store
+/// multi-byte integers in a fixed byte order for that to hold, e.g. with the types from `zerocopy::byteorder`.
+/// Since the bytes are hashed as they are, it must not contain `usize` or `isize` either,
+/// whose size differs between targets; use `derive_hash_fast_fields!(portable, ...)` for those.
+///
+/// A write plan other than [`Optimal`](crate::Optimal) is selected with a `plan = ...,` prefix
+/// before everything else, e.g. `derive_hash_fast!(plan = SingleWrite, zerocopy, MyType)`.
+/// See [`WritePlan`](crate::WritePlan).
+///
+/// # Examples
+///
+/// ```
+/// use derive_hash_fast::derive_hash_fast;
+/// use zerocopy::{Immutable, IntoBytes};
+///
+/// #[derive(Eq, PartialEq, Immutable, IntoBytes)]
+/// struct MyStruct {
+///     a: bool,
+///     b: u8,
+///     c: u16,
+/// }
+///
+/// derive_hash_fast!(zerocopy, MyStruct);
+///
+/// let mut hashset = std::collections::HashSet::new();
+/// hashset.insert(MyStruct{a: true, b: 2, c: 3});
+/// assert!(hashset.contains(&MyStruct{a: true, b: 2, c: 3}));
+/// ```
+#[macro_export]
+macro_rules! derive_hash_fast {
+    (@impl $hash:ident $slice:ident $plan:ty, $backend:ident [$($generics:tt)*] [$T:ty] [$($where:tt)*]) => {
+        $crate::derive_hash_fast!(@as_hash_bytes $backend [$($generics)*] [$T] [$($where)*]);
+
+        impl<$($generics)*> ::core::hash::Hash for $T where $($where)* {
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                $crate::$hash::<$plan, Self>(self, state);
+            }
+
+            fn hash_slice<H: ::core::hash::Hasher>(data: &[Self], state: &mut H)
+            where
+                Self: Sized,
+            {
+                $crate::$slice(data, state);
+            }
+        }
+    };
+    (@as_hash_bytes zerocopy [$($generics:tt)*] [$T:ty] [$($where:tt)*]) => {
+        // SAFETY: `zerocopy::IntoBytes` guarantees that the type has no padding,
+        // and the bytes of a slice are the bytes of its elements
+        unsafe impl<$($generics)*> $crate::AsHashBytes for $T where $($where)* {
+            const SIZE: usize = ::core::mem::size_of::<Self>();
+
+            #[inline]
+            fn hash_bytes(&self) -> &[u8] {
+                $crate::__zerocopy!(IntoBytes::as_bytes(self))
+            }
+
+            #[inline]
+            fn slice_bytes(s: &[Self]) -> &[u8] {
+                $crate::__zerocopy!(IntoBytes::as_bytes(s))
+            }
+        }
+    };
+    (@as_hash_bytes bytemuck [$($generics:tt)*] [$T:ty] [$($where:tt)*]) => {
+        // SAFETY: `bytemuck::NoUninit` guarantees that the type has no padding,
+        // and the bytes of a slice are the bytes of its elements
+        unsafe impl<$($generics)*> $crate::AsHashBytes for $T where $($where)* {
+            const SIZE: usize = ::core::mem::size_of::<Self>();
+
+            #[inline]
+            fn hash_bytes(&self) -> &[u8] {
+                $crate::__bytemuck!(bytes_of(self))
+            }
+
+            #[inline]
+            fn slice_bytes(s: &[Self]) -> &[u8] {
+                $crate::__bytemuck!(cast_slice(s))
+            }
+        }
+    };
+    // the type already implements `AsHashBytes`
+    (@as_hash_bytes existing [$($generics:tt)*] [$T:ty] [$($where:tt)*]) => {};
+    (@backend $hash:ident $slice:ident $plan:ty, $backend:ident impl < $($rest:tt)*) => {
+        $crate::__split_impl_generics!(derive_hash_fast {$hash $slice $plan, $backend} [] $($rest)*);
+    };
+    (@backend $hash:ident $slice:ident $plan:ty, $backend:ident $T:ty) => {
+        $crate::derive_hash_fast!(@impl $hash $slice $plan, $backend [] [$T] []);
+    };
+    (@mode $hash:ident $slice:ident $plan:ty, zerocopy, $($input:tt)*) => {
+        $crate::derive_hash_fast!(@backend $hash $slice $plan, zerocopy $($input)*);
+    };
+    (@mode $hash:ident $slice:ident $plan:ty, bytemuck, $($input:tt)*) => {
+        $crate::derive_hash_fast!(@backend $hash $slice $plan, bytemuck $($input)*);
+    };
+    (@mode $hash:ident $slice:ident $plan:ty, $($input:tt)*) => {
+        $crate::derive_hash_fast!(@backend $hash $slice $plan, existing $($input)*);
+    };
+    (@plan $plan:ty, portable, $($input:tt)*) => {
+        $crate::derive_hash_fast!(@mode hash_as_bytes_portable hash_slice_as_bytes_portable $plan, $($input)*);
+    };
+    (@plan $plan:ty, $($input:tt)*) => {
+        $crate::derive_hash_fast!(@mode hash_as_bytes hash_slice_as_bytes $plan, $($input)*);
+    };
+    (plan = $plan:ty, $($input:tt)*) => {
+        $crate::derive_hash_fast!(@plan $plan, $($input)*);
+    };
+    ($($input:tt)*) => {
+        $crate::derive_hash_fast!(@plan $crate::Optimal, $($input)*);
+    };
+}
+
+/// Derives a fast `Hash` implementation by viewing the type as raw bytes,
+/// for types without padding that can't implement the traits of `bytemuck` or `zerocopy`,
+/// such as structs generated from FFI headers.
+///
+/// `unsafe_derive_hash_fast!(MyType; a, b, c)` lists every field of the type, each of them once.
+/// It fails to compile if a field is missing, repeated or unknown, or unless `size_of::<MyType>()` equals
+/// the sum of the sizes of the listed fields, so padding between or after the fields is caught at compile time.
+/// Then it implements [`AsHashBytes`] with a raw byte view and invokes
+/// [`derive_hash_fast!`](crate::derive_hash_fast) on it.
+///
+/// Tuple structs are supported by listing field indices, e.g. `unsafe_derive_hash_fast!(MyTuple; 0, 1)`.
+///
+/// # Safety
+///
+/// The size check can only see the fields listed at the top level. The caller must ensure that:
+///
+/// - the type of every field has no padding or uninitialized bytes of its own, e.g. it is not
+///   a struct with padding, a union, a `MaybeUninit` or an enum with fields;
+/// - two values that compare equal have the same bytes, which rules out floats
+///   unless their `PartialEq` compares the bits.
+///
+/// # Examples
+///
+/// ```
+/// use derive_hash_fast::unsafe_derive_hash_fast;
+///
+/// // as generated by bindgen
+/// #[repr(C)]
+/// #[derive(Eq, PartialEq)]
+/// struct timespec {
+///     tv_sec: i64,
+///     tv_nsec: i64,
+/// }
+///
+/// unsafe_derive_hash_fast!(timespec; tv_sec, tv_nsec);
+///
+/// let mut hashset = std::collections::HashSet::new();
+/// hashset.insert(timespec { tv_sec: 1, tv_nsec: 2 });
+/// assert!(hashset.contains(&timespec { tv_sec: 1, tv_nsec: 2 }));
+/// ```
+///
+/// A forgotten padding byte fails to compile:
+///
+/// ```compile_fail
+/// use derive_hash_fast::unsafe_derive_hash_fast;
+///
+/// #[repr(C)]
+/// struct Padded {
+///     a: u8,
+///     b: u32,
+/// }
+///
+/// unsafe_derive_hash_fast!(Padded; a, b);
+/// ```
+///
+/// So does listing a field twice to make up for the padding:
+///
+/// ```compile_fail
+/// use derive_hash_fast::unsafe_derive_hash_fast;
+///
+/// #[repr(C)]
+/// struct Padded {
+///     a: u8,
+///     b: u32,
+/// }
+///
+/// unsafe_derive_hash_fast!(Padded; b, b);
+/// ```
+#[macro_export]
+macro_rules! unsafe_derive_hash_fast {
+    ($T:ty; $($field:tt),+ $(,)?) => {
+        const _: () = ::core::assert!(
+            ::core::mem::size_of::<$T>() == 0 $(+ $crate::raw_field_size(|s: &$T| &s.$field))+,
+            "the type has padding, or not all of its fields are listed",
+        );
+
+        // SAFETY: the assertion above and the pattern in `hash_bytes` rule out padding between the fields,
+        // and the caller of `unsafe_derive_hash_fast!` guarantees the rest
+        unsafe impl $crate::AsHashBytes for $T {
+            const SIZE: usize = ::core::mem::size_of::<Self>();
+
+            #[inline]
+            fn hash_bytes(&self) -> &[u8] {
+                // fails to compile unless every field is listed exactly once, so that the size check above
+                // can't be satisfied by listing a field twice instead of the padding
+                let Self { $($field: _),+ } = self;
+                unsafe { ::core::slice::from_raw_parts((self as *const Self).cast::<u8>(), Self::SIZE) }
+            }
+
+            #[inline]
+            fn slice_bytes(s: &[Self]) -> &[u8] {
+                unsafe { ::core::slice::from_raw_parts(s.as_ptr().cast::<u8>(), ::core::mem::size_of_val(s)) }
+            }
+        }
+
+        $crate::derive_hash_fast!($T);
+    };
+}
+
+/// Returns the in-memory size of the field selected by `_field`, including its own padding.
+#[doc(hidden)]
+pub const fn raw_field_size<T, F>(_field: fn(&T) -> &F) -> usize {
+    core::mem::size_of::<F>()
+}
+
+/// Hashes `value` with the plan `P` and the bytes returned by [`AsHashBytes`].
+#[doc(hidden)]
+#[inline]
+pub fn hash_as_bytes<P: WritePlan, T: AsHashBytes>(value: &T, state: &mut impl Hasher) {
+    let bytes = value.hash_bytes();
+    assert!(bytes.len() == T::SIZE);
+    P::write::<NativeEndian>(bytes, state)
+}
+
+/// Same as [`hash_as_bytes`], but the bytes are loaded in little-endian order,
+/// as in [`hash_fixed_bytes_portable`](crate::hash_fixed_bytes_portable).
+#[doc(hidden)]
+#[inline]
+pub fn hash_as_bytes_portable<P: WritePlan, T: AsHashBytes>(value: &T, state: &mut impl Hasher) {
+    let bytes = value.hash_bytes();
+    assert!(bytes.len() == T::SIZE);
+    P::write::<LittleEndian>(bytes, state)
+}
+
+/// Hashes a slice through [`hash_slice_bytes`](crate::hash_slice_bytes) with the bytes returned by [`AsHashBytes`].
+#[doc(hidden)]
+#[inline]
+pub fn hash_slice_as_bytes<T: AsHashBytes>(data: &[T], state: &mut impl Hasher) {
+    crate::hash_slice_bytes(T::slice_bytes(data), state)
+}
+
+/// Same as [`hash_slice_as_bytes`], but through [`hash_slice_bytes_portable`](crate::hash_slice_bytes_portable).
+#[doc(hidden)]
+#[inline]
+pub fn hash_slice_as_bytes_portable<T: AsHashBytes>(data: &[T], state: &mut impl Hasher) {
+    crate::hash_slice_bytes_portable(T::slice_bytes(data), state)
+}
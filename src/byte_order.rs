@@ -0,0 +1,52 @@
+/// How chunks of a byte representation are loaded into the integers passed to the hasher.
+///
+/// Public but unnameable outside this crate, so that it can appear in [`WritePlan`](crate::WritePlan).
+pub trait ByteOrder {
+    fn u16(bytes: [u8; 2]) -> u16;
+    fn u32(bytes: [u8; 4]) -> u32;
+    fn u64(bytes: [u8; 8]) -> u64;
+    fn u128(bytes: [u8; 16]) -> u128;
+}
+
+/// The byte order of the target. This is the fastest, but the integers passed
+/// to the hasher differ between little- and big-endian targets.
+pub struct NativeEndian;
+
+/// Used by the portable mode, so that the integers passed to the hasher
+/// only depend on the bytes being hashed and not on the target.
+pub struct LittleEndian;
+
+/// Simulates the native byte order of a big-endian target in tests.
+#[cfg(test)]
+pub(crate) struct BigEndian;
+
+macro_rules! impl_byte_order {
+    ($name:ident, $from_bytes:ident) => {
+        impl ByteOrder for $name {
+            #[inline(always)]
+            fn u16(bytes: [u8; 2]) -> u16 {
+                u16::$from_bytes(bytes)
+            }
+
+            #[inline(always)]
+            fn u32(bytes: [u8; 4]) -> u32 {
+                u32::$from_bytes(bytes)
+            }
+
+            #[inline(always)]
+            fn u64(bytes: [u8; 8]) -> u64 {
+                u64::$from_bytes(bytes)
+            }
+
+            #[inline(always)]
+            fn u128(bytes: [u8; 16]) -> u128 {
+                u128::$from_bytes(bytes)
+            }
+        }
+    };
+}
+
+impl_byte_order!(NativeEndian, from_ne_bytes);
+impl_byte_order!(LittleEndian, from_le_bytes);
+#[cfg(test)]
+impl_byte_order!(BigEndian, from_be_bytes);
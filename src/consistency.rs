@@ -0,0 +1,183 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+use arbitrary::{Arbitrary, Unstructured};
+
+use crate::{RecordedWrite, RecordingHasher};
+
+/// The number of values generated from random bytes.
+const VALUES: usize = 256;
+/// Fixed so that a failure is reproducible.
+const SEED: u64 = 0x2545_f491_4f6c_dd1d;
+
+/// Two values found by [`check_hash_eq_consistency`] that are equal but hash differently.
+///
+/// Implements `std::error::Error` with the `std` feature.
+#[derive(Clone, Debug)]
+pub struct HashEqInconsistency<T> {
+    pub a: T,
+    pub b: T,
+    /// The offsets of the bytes that differ between what `a` and `b` pass to the hasher.
+    /// These are the fields the `PartialEq` implementation ignores, but the hash does not.
+    ///
+    /// For a type hashed as its own bytes, e.g. with [`derive_hash_fast_bytemuck!`](crate::derive_hash_fast_bytemuck),
+    /// these are offsets into the value. For [`derive_hash_fast_fields!`](crate::derive_hash_fast_fields)
+    /// they are offsets into the buffer the listed fields are packed into, in the order they are listed.
+    pub offsets: Vec<usize>,
+}
+
+impl<T: fmt::Debug> fmt::Display for HashEqInconsistency<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} and {:?} are equal but hash differently, their bytes differ at offsets {:?}",
+            self.a, self.b, self.offsets
+        )
+    }
+}
+
+// `core::error::Error` needs Rust 1.81
+#[cfg(feature = "std")]
+impl<T: fmt::Debug> std::error::Error for HashEqInconsistency<T> {}
+
+/// Looks for values of `T` that are equal according to `PartialEq`, but hash differently with `H`.
+///
+/// The macros hash the bytes of a value, so `Hash` and `Eq` only agree if `PartialEq` compares
+/// all of those bytes and nothing else. A hand-written or separately derived `PartialEq` that
+/// ignores a field, e.g. a cache, breaks that contract and with it every `HashMap` the type is a key of.
+/// [`derive_hash_eq_fast_bytemuck!`](crate::derive_hash_eq_fast_bytemuck) and
+/// [`derive_hash_eq_fast_zerocopy!`](crate::derive_hash_eq_fast_zerocopy) rule this out by construction;
+/// this function checks the other types in a unit test.
+///
+/// Values are generated with [`arbitrary`](https://docs.rs/arbitrary), the same way `benches/hashset.rs`
+/// of this crate generates its keys, from pseudo-random bytes with a fixed seed.
+/// Every generated value is compared to the values generated from the same bytes with a single byte changed,
+/// which is how values that differ in a single field are found, and to every other generated value.
+/// The first pair that is equal but hashes differently is returned along with the offsets of the bytes
+/// they differ in, among the bytes of all the integers and byte slices their `Hash` implementations write.
+/// Not finding one is not a proof of consistency.
+///
+/// Requires the `testing` feature, and `T` to implement `Arbitrary` from version 1 of `arbitrary`.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "testing")] {
+/// use derive_hash_fast::{check_hash_eq_consistency, derive_hash_fast_bytemuck};
+/// use std::hash::DefaultHasher;
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, Debug, arbitrary::Arbitrary, bytemuck::NoUninit)]
+/// struct Entry {
+///     key: u32,
+///     cached_len: u32,
+/// }
+///
+/// // only compares the key
+/// impl PartialEq for Entry {
+///     fn eq(&self, other: &Self) -> bool {
+///         self.key == other.key
+///     }
+/// }
+///
+/// derive_hash_fast_bytemuck!(Entry);
+///
+/// let inconsistency = check_hash_eq_consistency::<Entry, DefaultHasher>().unwrap_err();
+/// assert!(inconsistency.offsets.iter().all(|offset| (4..8).contains(offset)));
+/// # }
+/// ```
+pub fn check_hash_eq_consistency<T, H>() -> Result<(), HashEqInconsistency<T>>
+where
+    T: for<'a> Arbitrary<'a> + Hash + PartialEq + Clone,
+    H: Hasher + Default,
+{
+    let mut rng = SplitMix64(SEED);
+    // `arbitrary` takes some of the bytes from the end, e.g. for enum discriminants,
+    // so leave room for both ends to be mutated independently
+    let mut data = vec![0u8; 2 * core::mem::size_of::<T>() + 64];
+    let mut values = Vec::with_capacity(VALUES);
+    for _ in 0..VALUES {
+        rng.fill(&mut data);
+        let Ok(a) = T::arbitrary(&mut Unstructured::new(&data)) else {
+            continue;
+        };
+        for i in 0..data.len() {
+            let original = data[i];
+            data[i] ^= rng.nonzero_byte();
+            if let Ok(b) = T::arbitrary(&mut Unstructured::new(&data)) {
+                check_pair::<T, H>(&a, &b)?;
+            }
+            data[i] = original;
+        }
+        values.push(a);
+    }
+    for (i, a) in values.iter().enumerate() {
+        for b in &values[i + 1..] {
+            check_pair::<T, H>(a, b)?;
+        }
+    }
+    Ok(())
+}
+
+fn check_pair<T, H>(a: &T, b: &T) -> Result<(), HashEqInconsistency<T>>
+where
+    T: Hash + PartialEq + Clone,
+    H: Hasher + Default,
+{
+    if a == b && hash_with::<H>(a) != hash_with::<H>(b) {
+        let (a_bytes, b_bytes) = (written_bytes(a), written_bytes(b));
+        let offsets = (0..a_bytes.len().max(b_bytes.len()))
+            .filter(|&offset| a_bytes.get(offset) != b_bytes.get(offset))
+            .collect();
+        return Err(HashEqInconsistency { a: a.clone(), b: b.clone(), offsets });
+    }
+    Ok(())
+}
+
+/// Returns the bytes `value` passes to a hasher, with the integers in native byte order.
+fn written_bytes(value: &impl Hash) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for write in RecordingHasher::record(value).into_writes() {
+        match write {
+            RecordedWrite::U8(i) => bytes.extend_from_slice(&i.to_ne_bytes()),
+            RecordedWrite::U16(i) => bytes.extend_from_slice(&i.to_ne_bytes()),
+            RecordedWrite::U32(i) => bytes.extend_from_slice(&i.to_ne_bytes()),
+            RecordedWrite::U64(i) => bytes.extend_from_slice(&i.to_ne_bytes()),
+            RecordedWrite::U128(i) => bytes.extend_from_slice(&i.to_ne_bytes()),
+            RecordedWrite::Usize(i) => bytes.extend_from_slice(&i.to_ne_bytes()),
+            RecordedWrite::Bytes(written) => bytes.extend_from_slice(&written),
+        }
+    }
+    bytes
+}
+
+fn hash_with<H: Hasher + Default>(value: &impl Hash) -> u64 {
+    let mut hasher = H::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A small pseudo-random number generator, good enough to feed `arbitrary` in `#![no_std]`.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn fill(&mut self, data: &mut [u8]) {
+        for chunk in data.chunks_mut(8) {
+            chunk.copy_from_slice(&self.next().to_le_bytes()[..chunk.len()]);
+        }
+    }
+
+    fn nonzero_byte(&mut self) -> u8 {
+        (self.next() as u8).max(1)
+    }
+}
@@ -0,0 +1,234 @@
+use crate::byte_order::NativeEndian;
+use crate::{pad_to_u128, pad_to_u32, pad_to_u64};
+
+/// Derives a fast `PartialEq` and `Eq` implementation for `bytemuck` types.
+///
+/// This macro compares the byte representations of two values obtained through
+/// `bytemuck::bytes_of()`, using the same loads of `u32`, `u64` and `u128` that
+/// [`derive_hash_fast_bytemuck!`](crate::derive_hash_fast_bytemuck) feeds to the hasher.
+/// Two values are equal if and only if their hashes are computed from the same bytes,
+/// so `Hash` and `Eq` agree by construction.
+///
+/// Note that this is not equivalent to `#[derive(PartialEq)]` for types containing floats:
+/// `0.0` and `-0.0` are not equal, while two NaNs with the same bits are.
+///
+/// Generic types are supported with the same `impl<...> for Type` syntax
+/// as [`derive_hash_fast_bytemuck!`](crate::derive_hash_fast_bytemuck).
+///
+/// # Examples
+///
+/// ```
+/// use derive_hash_fast::derive_eq_fast_bytemuck;
+/// use bytemuck::NoUninit;
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, NoUninit)]
+/// struct MyStruct {
+///     a: u32,
+///     b: u16,
+///     c: u16,
+/// }
+///
+/// derive_eq_fast_bytemuck!(MyStruct);
+///
+/// assert!(MyStruct{a: 1, b: 2, c: 3} == MyStruct{a: 1, b: 2, c: 3});
+/// assert!(MyStruct{a: 1, b: 2, c: 3} != MyStruct{a: 1, b: 3, c: 2});
+/// ```
+#[macro_export]
+macro_rules! derive_eq_fast_bytemuck {
+    (@impl [$($generics:tt)*] [$T:ty] [$($where:tt)*]) => {
+        impl<$($generics)*> ::core::cmp::PartialEq for $T where $($where)* {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                $crate::bytes_eq_for::<Self>($crate::__bytemuck!(bytes_of(self)), $crate::__bytemuck!(bytes_of(other)))
+            }
+        }
+
+        impl<$($generics)*> ::core::cmp::Eq for $T where $($where)* {}
+    };
+    (impl < $($rest:tt)*) => {
+        $crate::__split_impl_generics!(derive_eq_fast_bytemuck {} [] $($rest)*);
+    };
+    ($T:ty) => {
+        $crate::derive_eq_fast_bytemuck!(@impl [] [$T] []);
+    };
+}
+
+/// Derives a fast `PartialEq` and `Eq` implementation for `zerocopy` types.
+///
+/// This macro compares the byte representations of two values obtained through
+/// `.as_bytes()`, using the same loads of `u32`, `u64` and `u128` that
+/// [`derive_hash_fast_zerocopy!`](crate::derive_hash_fast_zerocopy) feeds to the hasher.
+/// Two values are equal if and only if their hashes are computed from the same bytes,
+/// so `Hash` and `Eq` agree by construction.
+///
+/// Note that this is not equivalent to `#[derive(PartialEq)]` for types containing floats:
+/// `0.0` and `-0.0` are not equal, while two NaNs with the same bits are.
+///
+/// Generic types are supported with the same `impl<...> for Type` syntax
+/// as [`derive_hash_fast_zerocopy!`](crate::derive_hash_fast_zerocopy).
+///
+/// # Examples
+///
+/// ```
+/// use derive_hash_fast::derive_eq_fast_zerocopy;
+/// use zerocopy::{Immutable, IntoBytes};
+///
+/// #[derive(Immutable, IntoBytes)]
+/// struct MyStruct {
+///     a: u32,
+///     b: u16,
+///     c: u16,
+/// }
+///
+/// derive_eq_fast_zerocopy!(MyStruct);
+///
+/// assert!(MyStruct{a: 1, b: 2, c: 3} == MyStruct{a: 1, b: 2, c: 3});
+/// assert!(MyStruct{a: 1, b: 2, c: 3} != MyStruct{a: 1, b: 3, c: 2});
+/// ```
+#[macro_export]
+macro_rules! derive_eq_fast_zerocopy {
+    (@impl [$($generics:tt)*] [$T:ty] [$($where:tt)*]) => {
+        impl<$($generics)*> ::core::cmp::PartialEq for $T where $($where)* {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                $crate::bytes_eq_for::<Self>(
+                    $crate::__zerocopy!(IntoBytes::as_bytes(self)),
+                    $crate::__zerocopy!(IntoBytes::as_bytes(other)),
+                )
+            }
+        }
+
+        impl<$($generics)*> ::core::cmp::Eq for $T where $($where)* {}
+    };
+    (impl < $($rest:tt)*) => {
+        $crate::__split_impl_generics!(derive_eq_fast_zerocopy {} [] $($rest)*);
+    };
+    ($T:ty) => {
+        $crate::derive_eq_fast_zerocopy!(@impl [] [$T] []);
+    };
+}
+
+/// Derives fast `Hash`, `PartialEq` and `Eq` implementations for `bytemuck` types.
+///
+/// Shorthand for invoking both [`derive_hash_fast_bytemuck!`](crate::derive_hash_fast_bytemuck)
+/// and [`derive_eq_fast_bytemuck!`](crate::derive_eq_fast_bytemuck), and accepts the same syntax.
+/// A `plan = ...,` prefix selects the [`WritePlan`](crate::WritePlan) of the `Hash` implementation,
+/// e.g. `derive_hash_eq_fast_bytemuck!(plan = U64Only, MyType)`; `Eq` is derived the same way with any plan.
+///
+/// # Examples
+///
+/// ```
+/// use derive_hash_fast::derive_hash_eq_fast_bytemuck;
+/// use bytemuck::NoUninit;
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, NoUninit)]
+/// struct MyStruct {
+///     a: u32,
+///     b: u16,
+///     c: u16,
+/// }
+///
+/// derive_hash_eq_fast_bytemuck!(MyStruct);
+///
+/// let mut hashset = std::collections::HashSet::new();
+/// hashset.insert(MyStruct{a: 1, b: 2, c: 3});
+/// assert!(hashset.contains(&MyStruct{a: 1, b: 2, c: 3}));
+/// ```
+#[macro_export]
+macro_rules! derive_hash_eq_fast_bytemuck {
+    (plan = $plan:ty, $($input:tt)*) => {
+        $crate::derive_hash_fast_bytemuck!(plan = $plan, $($input)*);
+        $crate::derive_eq_fast_bytemuck!($($input)*);
+    };
+    ($($input:tt)*) => {
+        $crate::derive_hash_fast_bytemuck!($($input)*);
+        $crate::derive_eq_fast_bytemuck!($($input)*);
+    };
+}
+
+/// Derives fast `Hash`, `PartialEq` and `Eq` implementations for `zerocopy` types.
+///
+/// Shorthand for invoking both [`derive_hash_fast_zerocopy!`](crate::derive_hash_fast_zerocopy)
+/// and [`derive_eq_fast_zerocopy!`](crate::derive_eq_fast_zerocopy), and accepts the same syntax.
+/// A `plan = ...,` prefix selects the [`WritePlan`](crate::WritePlan) of the `Hash` implementation,
+/// e.g. `derive_hash_eq_fast_zerocopy!(plan = U64Only, MyType)`; `Eq` is derived the same way with any plan.
+///
+/// # Examples
+///
+/// ```
+/// use derive_hash_fast::derive_hash_eq_fast_zerocopy;
+/// use zerocopy::{Immutable, IntoBytes};
+///
+/// #[derive(Immutable, IntoBytes)]
+/// struct MyStruct {
+///     a: u32,
+///     b: u16,
+///     c: u16,
+/// }
+///
+/// derive_hash_eq_fast_zerocopy!(MyStruct);
+///
+/// let mut hashset = std::collections::HashSet::new();
+/// hashset.insert(MyStruct{a: 1, b: 2, c: 3});
+/// assert!(hashset.contains(&MyStruct{a: 1, b: 2, c: 3}));
+/// ```
+#[macro_export]
+macro_rules! derive_hash_eq_fast_zerocopy {
+    (plan = $plan:ty, $($input:tt)*) => {
+        $crate::derive_hash_fast_zerocopy!(plan = $plan, $($input)*);
+        $crate::derive_eq_fast_zerocopy!($($input)*);
+    };
+    ($($input:tt)*) => {
+        $crate::derive_hash_fast_zerocopy!($($input)*);
+        $crate::derive_eq_fast_zerocopy!($($input)*);
+    };
+}
+
+/// Compares two byte representations of `T`,
+/// using the same size classes as [`hash_fixed_bytes`](crate::hash_fixed_bytes).
+#[doc(hidden)]
+#[inline]
+pub fn bytes_eq_for<T>(a: &[u8], b: &[u8]) -> bool {
+    bytes_eq_of_constant_size(core::mem::size_of::<T>(), a, b)
+}
+
+/// `size` must be a compile-time constant at every call site,
+/// which is why this is `#[inline(always)]`.
+#[inline(always)]
+fn bytes_eq_of_constant_size(size: usize, a: &[u8], b: &[u8]) -> bool {
+    assert!(a.len() == size && b.len() == size);
+    // This match incurs no runtime overhead in release mode because it matches on a constant.
+    match size {
+        1 => a[0] == b[0],
+        2 => u16::from_ne_bytes(a.try_into().unwrap()) == u16::from_ne_bytes(b.try_into().unwrap()),
+        3 => pad_to_u32::<NativeEndian>(a) == pad_to_u32::<NativeEndian>(b),
+        4 => u32::from_ne_bytes(a.try_into().unwrap()) == u32::from_ne_bytes(b.try_into().unwrap()),
+        5..=7 => pad_to_u64::<NativeEndian>(a) == pad_to_u64::<NativeEndian>(b),
+        8 => u64::from_ne_bytes(a.try_into().unwrap()) == u64::from_ne_bytes(b.try_into().unwrap()),
+        9..=15 => pad_to_u128::<NativeEndian>(a) == pad_to_u128::<NativeEndian>(b),
+        16 => u128::from_ne_bytes(a.try_into().unwrap()) == u128::from_ne_bytes(b.try_into().unwrap()),
+        17.. => eq_padded_large(a, b),
+        0 => true,
+    }
+}
+
+/// Compares in `u128` chunks, with the tail padded the same way as in `hash_padded_large`.
+#[inline(always)]
+fn eq_padded_large(a: &[u8], b: &[u8]) -> bool {
+    const SIZEOF_U128: usize = core::mem::size_of::<u128>();
+    let a_chunks = a.chunks_exact(SIZEOF_U128);
+    let b_chunks = b.chunks_exact(SIZEOF_U128);
+    let (a_remainder, b_remainder) = (a_chunks.remainder(), b_chunks.remainder());
+    let chunks_equal = a_chunks.zip(b_chunks).all(|(a, b)| {
+        u128::from_ne_bytes(a.try_into().unwrap()) == u128::from_ne_bytes(b.try_into().unwrap())
+    });
+    chunks_equal
+        && match a_remainder.len() {
+            0 => true,
+            1..=8 => pad_to_u64::<NativeEndian>(a_remainder) == pad_to_u64::<NativeEndian>(b_remainder),
+            9..=15 => pad_to_u128::<NativeEndian>(a_remainder) == pad_to_u128::<NativeEndian>(b_remainder),
+            SIZEOF_U128.. => unreachable!(),
+        }
+}
@@ -0,0 +1,360 @@
+use crate::float::Canonicalize;
+
+/// Types that [`derive_hash_fast_fields!`](crate::derive_hash_fast_fields) can copy
+/// into its packed buffer.
+///
+/// Implemented for primitive integers, floats, `bool`, `char`, raw pointers, and arrays and tuples of those.
+/// Implement it for your own types to use them as fields of a type hashed with
+/// [`derive_hash_fast_fields!`](crate::derive_hash_fast_fields).
+///
+/// # Examples
+///
+/// ```
+/// use derive_hash_fast::HashField;
+///
+/// #[derive(Clone, Copy)]
+/// struct Rgb {
+///     r: u8,
+///     g: u8,
+///     b: u8,
+/// }
+///
+/// impl HashField for Rgb {
+///     const SIZE: usize = 3;
+///     // bytes are the same on every target
+///     const PORTABLE_SIZE: usize = 3;
+///
+///     fn write_bytes(&self, out: &mut [u8]) {
+///         out.copy_from_slice(&[self.r, self.g, self.b]);
+///     }
+///
+///     fn write_portable_bytes(&self, out: &mut [u8]) {
+///         self.write_bytes(out);
+///     }
+/// }
+/// ```
+///
+/// Leaving out the portable size or bytes fails to compile, since a type with `usize` fields
+/// would otherwise hash differently on 32-bit and 64-bit targets with `portable`:
+///
+/// ```compile_fail
+/// use derive_hash_fast::HashField;
+///
+/// struct Len(usize);
+///
+/// impl HashField for Len {
+///     const SIZE: usize = core::mem::size_of::<usize>();
+///
+///     fn write_bytes(&self, out: &mut [u8]) {
+///         self.0.write_bytes(out);
+///     }
+/// }
+/// ```
+pub trait HashField {
+    /// The number of bytes written by [`HashField::write_bytes`].
+    const SIZE: usize;
+
+    /// The number of bytes written by [`HashField::write_portable_bytes`].
+    ///
+    /// Same as [`HashField::SIZE`] unless the type contains pointer-sized integers,
+    /// which take 8 bytes on every target. There is no default, so that a type containing them
+    /// can't silently inherit its native size.
+    const PORTABLE_SIZE: usize;
+
+    /// Writes the bytes representing `self` into `out`, which is exactly [`HashField::SIZE`] bytes long.
+    fn write_bytes(&self, out: &mut [u8]);
+
+    /// Same as [`HashField::write_bytes`], but floats are canonicalized first:
+    /// `-0.0` is written as `0.0`, and every NaN as the same canonical NaN.
+    ///
+    /// Used by `derive_hash_fast_fields!(canonical_floats, ...)`.
+    /// Types containing floats should forward this to their float fields.
+    #[inline]
+    fn write_canonical_bytes(&self, out: &mut [u8]) {
+        self.write_bytes(out);
+    }
+
+    /// Same as [`HashField::write_bytes`], but multi-byte integers and floats are written
+    /// in little-endian byte order, and `usize` and `isize` are widened to 64 bits,
+    /// so the bytes are the same on every target. `out` is exactly [`HashField::PORTABLE_SIZE`] bytes long.
+    ///
+    /// Used by `derive_hash_fast_fields!(portable, ...)`.
+    /// Types containing integers or floats should forward this to those fields.
+    /// There is no default, because the native bytes of those differ between targets.
+    fn write_portable_bytes(&self, out: &mut [u8]);
+}
+
+macro_rules! impl_hash_field_for_primitives {
+    ($($T:ty)*) => {
+        $(
+            impl HashField for $T {
+                const SIZE: usize = core::mem::size_of::<$T>();
+                const PORTABLE_SIZE: usize = core::mem::size_of::<$T>();
+
+                #[inline]
+                fn write_bytes(&self, out: &mut [u8]) {
+                    out.copy_from_slice(&self.to_ne_bytes());
+                }
+
+                #[inline]
+                fn write_portable_bytes(&self, out: &mut [u8]) {
+                    out.copy_from_slice(&self.to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_hash_field_for_primitives!(u8 u16 u32 u64 u128 i8 i16 i32 i64 i128);
+
+macro_rules! impl_hash_field_for_pointer_sized {
+    ($($T:ty => $Wide:ty),*) => {
+        $(
+            impl HashField for $T {
+                const SIZE: usize = core::mem::size_of::<$T>();
+                const PORTABLE_SIZE: usize = core::mem::size_of::<$Wide>();
+
+                #[inline]
+                fn write_bytes(&self, out: &mut [u8]) {
+                    out.copy_from_slice(&self.to_ne_bytes());
+                }
+
+                #[inline]
+                fn write_portable_bytes(&self, out: &mut [u8]) {
+                    // lossless on every target Rust supports
+                    out.copy_from_slice(&(*self as $Wide).to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_hash_field_for_pointer_sized!(usize => u64, isize => i64);
+
+macro_rules! impl_hash_field_for_floats {
+    ($($T:ty)*) => {
+        $(
+            impl HashField for $T {
+                const SIZE: usize = core::mem::size_of::<$T>();
+                const PORTABLE_SIZE: usize = core::mem::size_of::<$T>();
+
+                #[inline]
+                fn write_bytes(&self, out: &mut [u8]) {
+                    out.copy_from_slice(&self.to_ne_bytes());
+                }
+
+                #[inline]
+                fn write_canonical_bytes(&self, out: &mut [u8]) {
+                    self.canonicalize().write_bytes(out);
+                }
+
+                #[inline]
+                fn write_portable_bytes(&self, out: &mut [u8]) {
+                    out.copy_from_slice(&self.to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_hash_field_for_floats!(f32 f64);
+
+impl HashField for bool {
+    const SIZE: usize = 1;
+    const PORTABLE_SIZE: usize = 1;
+
+    #[inline]
+    fn write_bytes(&self, out: &mut [u8]) {
+        out[0] = u8::from(*self);
+    }
+
+    #[inline]
+    fn write_portable_bytes(&self, out: &mut [u8]) {
+        self.write_bytes(out);
+    }
+}
+
+impl HashField for char {
+    const SIZE: usize = 4;
+    const PORTABLE_SIZE: usize = 4;
+
+    #[inline]
+    fn write_bytes(&self, out: &mut [u8]) {
+        u32::from(*self).write_bytes(out);
+    }
+
+    #[inline]
+    fn write_portable_bytes(&self, out: &mut [u8]) {
+        u32::from(*self).write_portable_bytes(out);
+    }
+}
+
+impl<T> HashField for *const T {
+    const SIZE: usize = core::mem::size_of::<usize>();
+    const PORTABLE_SIZE: usize = usize::PORTABLE_SIZE;
+
+    #[inline]
+    fn write_bytes(&self, out: &mut [u8]) {
+        (*self as usize).write_bytes(out);
+    }
+
+    #[inline]
+    fn write_portable_bytes(&self, out: &mut [u8]) {
+        (*self as usize).write_portable_bytes(out);
+    }
+}
+
+impl<T> HashField for *mut T {
+    const SIZE: usize = core::mem::size_of::<usize>();
+    const PORTABLE_SIZE: usize = usize::PORTABLE_SIZE;
+
+    #[inline]
+    fn write_bytes(&self, out: &mut [u8]) {
+        (*self as usize).write_bytes(out);
+    }
+
+    #[inline]
+    fn write_portable_bytes(&self, out: &mut [u8]) {
+        (*self as usize).write_portable_bytes(out);
+    }
+}
+
+impl<T: HashField, const N: usize> HashField for [T; N] {
+    const SIZE: usize = T::SIZE * N;
+    const PORTABLE_SIZE: usize = T::PORTABLE_SIZE * N;
+
+    #[inline]
+    fn write_bytes(&self, out: &mut [u8]) {
+        for (i, item) in self.iter().enumerate() {
+            item.write_bytes(&mut out[i * T::SIZE..(i + 1) * T::SIZE]);
+        }
+    }
+
+    #[inline]
+    fn write_canonical_bytes(&self, out: &mut [u8]) {
+        for (i, item) in self.iter().enumerate() {
+            item.write_canonical_bytes(&mut out[i * T::SIZE..(i + 1) * T::SIZE]);
+        }
+    }
+
+    #[inline]
+    fn write_portable_bytes(&self, out: &mut [u8]) {
+        for (i, item) in self.iter().enumerate() {
+            item.write_portable_bytes(&mut out[i * T::PORTABLE_SIZE..(i + 1) * T::PORTABLE_SIZE]);
+        }
+    }
+}
+
+impl HashField for () {
+    const SIZE: usize = 0;
+    const PORTABLE_SIZE: usize = 0;
+
+    #[inline]
+    fn write_bytes(&self, _out: &mut [u8]) {}
+
+    #[inline]
+    fn write_portable_bytes(&self, _out: &mut [u8]) {}
+}
+
+macro_rules! impl_hash_field_for_tuples {
+    ($(($($T:ident $idx:tt),+))*) => {
+        $(
+            impl<$($T: HashField),+> HashField for ($($T,)+) {
+                const SIZE: usize = 0 $(+ $T::SIZE)+;
+                const PORTABLE_SIZE: usize = 0 $(+ $T::PORTABLE_SIZE)+;
+
+                #[inline]
+                fn write_bytes(&self, out: &mut [u8]) {
+                    let mut offset = 0;
+                    $(
+                        self.$idx.write_bytes(&mut out[offset..offset + $T::SIZE]);
+                        offset += $T::SIZE;
+                    )+
+                    debug_assert_eq!(offset, Self::SIZE);
+                }
+
+                #[inline]
+                fn write_canonical_bytes(&self, out: &mut [u8]) {
+                    let mut offset = 0;
+                    $(
+                        self.$idx.write_canonical_bytes(&mut out[offset..offset + $T::SIZE]);
+                        offset += $T::SIZE;
+                    )+
+                    debug_assert_eq!(offset, Self::SIZE);
+                }
+
+                #[inline]
+                fn write_portable_bytes(&self, out: &mut [u8]) {
+                    let mut offset = 0;
+                    $(
+                        self.$idx.write_portable_bytes(&mut out[offset..offset + $T::PORTABLE_SIZE]);
+                        offset += $T::PORTABLE_SIZE;
+                    )+
+                    debug_assert_eq!(offset, Self::PORTABLE_SIZE);
+                }
+            }
+        )*
+    };
+}
+
+impl_hash_field_for_tuples! {
+    (A 0)
+    (A 0, B 1)
+    (A 0, B 1, C 2)
+    (A 0, B 1, C 2, D 3)
+    (A 0, B 1, C 2, D 3, E 4)
+    (A 0, B 1, C 2, D 3, E 4, F 5)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11)
+}
+
+/// Returns the packed size of the field selected by `_field`.
+///
+/// The field type is inferred from the accessor, so this can be evaluated
+/// in a `const` without naming the field's type.
+#[doc(hidden)]
+pub const fn packed_field_size<T, F: HashField>(_field: fn(&T) -> &F) -> usize {
+    F::SIZE
+}
+
+/// Same as [`packed_field_size`], for `derive_hash_fast_fields!(portable, ...)`.
+#[doc(hidden)]
+pub const fn packed_portable_field_size<T, F: HashField>(_field: fn(&T) -> &F) -> usize {
+    F::PORTABLE_SIZE
+}
+
+/// Returns the packed size of the value produced by `_value`.
+///
+/// Used to size the buffer for an enum variant's payload, which is copied out into a tuple.
+#[doc(hidden)]
+pub const fn packed_size_of<T, F: HashField>(_value: fn(&T) -> F) -> usize {
+    F::SIZE
+}
+
+/// Writes `field` into `buffer` at `offset` and returns the offset of the next field.
+#[doc(hidden)]
+#[inline(always)]
+pub fn write_packed_field<F: HashField>(field: &F, buffer: &mut [u8], offset: usize) -> usize {
+    field.write_bytes(&mut buffer[offset..offset + F::SIZE]);
+    offset + F::SIZE
+}
+
+/// Same as [`write_packed_field`], but canonicalizes floats.
+#[doc(hidden)]
+#[inline(always)]
+pub fn write_canonical_packed_field<F: HashField>(field: &F, buffer: &mut [u8], offset: usize) -> usize {
+    field.write_canonical_bytes(&mut buffer[offset..offset + F::SIZE]);
+    offset + F::SIZE
+}
+
+/// Same as [`write_packed_field`], but in little-endian byte order and with `usize` widened to 64 bits.
+#[doc(hidden)]
+#[inline(always)]
+pub fn write_portable_packed_field<F: HashField>(field: &F, buffer: &mut [u8], offset: usize) -> usize {
+    field.write_portable_bytes(&mut buffer[offset..offset + F::PORTABLE_SIZE]);
+    offset + F::PORTABLE_SIZE
+}
@@ -0,0 +1,206 @@
+use core::hash::{BuildHasher, Hasher};
+
+use crate::byte_order::LittleEndian;
+
+/// The fractional digits of pi, a multiplier without exploitable structure.
+const MULTIPLIER: u64 = 0x243f_6a88_85a3_08d3;
+/// Derives the multiplier for the high half of `write_u128` from the seed.
+const KEY_MULTIPLIER: u64 = 0x1319_8a2e_0370_7344;
+/// Used when no seed is given.
+const DEFAULT_SEED: u64 = 0xa409_3822_299f_31d0;
+
+/// Multiplies two `u64`s into a `u128` and folds the halves together with XOR.
+///
+/// A single instruction on most 64-bit targets. The result is zero if either input is zero.
+#[inline(always)]
+const fn folded_multiply(a: u64, b: u64) -> u64 {
+    let full = (a as u128).wrapping_mul(b as u128);
+    (full as u64) ^ ((full >> 64) as u64)
+}
+
+/// A fast, seeded hasher built for the fixed-size writes this crate makes.
+///
+/// The hashers in the benchmarks each have a slow path for some of those writes:
+/// many implement `write_u128` in terms of the variable-length `write`, or buffer
+/// every integer before mixing it. `FixedHasher` mixes every integer of up to 64 bits
+/// with a single folded multiply, and the halves of a `u128` with one multiply each.
+/// Integers narrower than 64 bits are widened to a `u64`.
+///
+/// It works in `#![no_std]` and doesn't allocate. The variable-length `write` is supported
+/// for types that don't use the macros, but isn't optimized.
+///
+/// **This is not a DoS-resistant hasher.** Even with a secret seed, an attacker who controls
+/// the keys can produce collisions. Use it for keys that are not controlled by untrusted input,
+/// the same as `rustc_hash::FxHasher`. The output is the same on every target with the same seed,
+/// but may change between versions of this crate, so do not persist it.
+///
+/// Requires the `fixed-hasher` feature.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "fixed-hasher")] {
+/// use derive_hash_fast::{derive_hash_fast_bytemuck, FixedBuildHasher};
+/// use std::collections::HashSet;
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, PartialEq, Eq, bytemuck::NoUninit)]
+/// struct Point {
+///     x: u32,
+///     y: u32,
+///     z: u32,
+///     w: u32,
+/// }
+///
+/// derive_hash_fast_bytemuck!(Point);
+///
+/// // a single `write_u128`, mixed with two multiplications
+/// let mut set = HashSet::with_hasher(FixedBuildHasher::new(0x1234));
+/// set.insert(Point { x: 1, y: 2, z: 3, w: 4 });
+/// assert!(set.contains(&Point { x: 1, y: 2, z: 3, w: 4 }));
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct FixedHasher {
+    state: u64,
+    /// The multiplier for the high half in `write_u128`. Derived from the seed, and never zero.
+    pub(crate) key: u64,
+}
+
+impl FixedHasher {
+    /// Creates a hasher with the given seed.
+    #[inline]
+    pub const fn with_seed(seed: u64) -> Self {
+        // neither the state nor the key is the seed itself, so that an input equal to a small seed
+        // doesn't cancel either of them out
+        Self {
+            state: folded_multiply(seed ^ MULTIPLIER, KEY_MULTIPLIER),
+            key: folded_multiply(seed ^ KEY_MULTIPLIER, MULTIPLIER) | 1,
+        }
+    }
+}
+
+impl Default for FixedHasher {
+    /// Creates a hasher with a fixed seed built into this crate.
+    #[inline]
+    fn default() -> Self {
+        Self::with_seed(DEFAULT_SEED)
+    }
+}
+
+impl Hasher for FixedHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        // the last write only went through one multiplication,
+        // one more spreads it over the high bits that hash tables use
+        folded_multiply(self.state, MULTIPLIER)
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        // the length keeps the zero padding of the last chunk from causing collisions
+        self.state ^= bytes.len() as u64;
+        for chunk in bytes.chunks(core::mem::size_of::<u128>()) {
+            self.write_u128(crate::pad_to_u128::<LittleEndian>(chunk));
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.write_u64(i.into())
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.write_u64(i.into())
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.write_u64(i.into())
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.state = folded_multiply(self.state ^ i, MULTIPLIER);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        let lo = i as u64;
+        let hi = (i >> 64) as u64;
+        // A single `folded_multiply(state ^ lo, hi ^ key)` would be symmetric in its operands,
+        // and zero whenever `hi == key`, throwing away the state. Mixing the halves one after the other
+        // keeps their order, and the product is only zero if a half equals the current state.
+        self.state = folded_multiply(self.state ^ lo, MULTIPLIER);
+        self.state = folded_multiply(self.state ^ hi, self.key);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64)
+    }
+
+    #[inline]
+    fn write_i8(&mut self, i: i8) {
+        self.write_u8(i as u8)
+    }
+
+    #[inline]
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16)
+    }
+
+    #[inline]
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32)
+    }
+
+    #[inline]
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64)
+    }
+
+    #[inline]
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128)
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.write_usize(i as usize)
+    }
+}
+
+/// Builds [`FixedHasher`]s with a seed, for use in `HashMap` and `HashSet`.
+///
+/// [`Default`] uses a fixed seed built into this crate, because `#![no_std]` has no source of randomness.
+/// Pass a random seed to [`FixedBuildHasher::new`] to make the hashes differ between runs.
+#[derive(Clone, Copy, Debug)]
+pub struct FixedBuildHasher {
+    seed: u64,
+}
+
+impl FixedBuildHasher {
+    /// Creates a `BuildHasher` whose hashers all start from `seed`.
+    #[inline]
+    pub const fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+}
+
+impl Default for FixedBuildHasher {
+    #[inline]
+    fn default() -> Self {
+        Self::new(DEFAULT_SEED)
+    }
+}
+
+impl BuildHasher for FixedBuildHasher {
+    type Hasher = FixedHasher;
+
+    #[inline]
+    fn build_hasher(&self) -> FixedHasher {
+        FixedHasher::with_seed(self.seed)
+    }
+}
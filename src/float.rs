@@ -0,0 +1,186 @@
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+
+use crate::HashField;
+
+macro_rules! ordered_float {
+    ($(#[$attr:meta])* $name:ident, $float:ty, $bits:ty, $write:ident) => {
+        $(#[$attr])*
+        #[repr(transparent)]
+        #[derive(Clone, Copy, Default)]
+        #[cfg_attr(feature = "zerocopy", derive(zerocopy::IntoBytes, zerocopy::Immutable, zerocopy::KnownLayout))]
+        pub struct $name($float);
+
+        impl $name {
+            /// Wraps `value`, replacing `-0.0` with `0.0` and any NaN with the canonical NaN.
+            #[inline]
+            pub fn new(value: $float) -> Self {
+                Self(value.canonicalize())
+            }
+
+            /// Returns the canonicalized value.
+            #[inline]
+            pub fn get(self) -> $float {
+                self.0
+            }
+        }
+
+        impl From<$float> for $name {
+            #[inline]
+            fn from(value: $float) -> Self {
+                Self::new(value)
+            }
+        }
+
+        impl From<$name> for $float {
+            #[inline]
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl PartialEq for $name {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                self.0.to_bits() == other.0.to_bits()
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl PartialOrd for $name {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $name {
+            /// Orders by value, with NaN greater than every other value.
+            #[inline]
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.0.total_cmp(&other.0)
+            }
+        }
+
+        impl Hash for $name {
+            #[inline]
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                state.$write(self.0.to_bits());
+            }
+        }
+
+        impl HashField for $name {
+            const SIZE: usize = core::mem::size_of::<$float>();
+            const PORTABLE_SIZE: usize = core::mem::size_of::<$float>();
+
+            #[inline]
+            fn write_bytes(&self, out: &mut [u8]) {
+                self.0.write_bytes(out);
+            }
+
+            #[inline]
+            fn write_portable_bytes(&self, out: &mut [u8]) {
+                self.0.write_portable_bytes(out);
+            }
+        }
+
+        // SAFETY: `repr(transparent)` over a float, which has no padding,
+        // and the value is canonicalized, so equal values have equal bytes
+        unsafe impl crate::AsHashBytes for $name {
+            const SIZE: usize = core::mem::size_of::<$float>();
+
+            #[inline]
+            fn hash_bytes(&self) -> &[u8] {
+                unsafe { core::slice::from_raw_parts((self as *const Self).cast::<u8>(), core::mem::size_of::<Self>()) }
+            }
+
+            #[inline]
+            fn slice_bytes(s: &[Self]) -> &[u8] {
+                unsafe { core::slice::from_raw_parts(s.as_ptr().cast::<u8>(), core::mem::size_of_val(s)) }
+            }
+        }
+
+        // SAFETY: `repr(transparent)` over a float, which has no padding
+        #[cfg(feature = "bytemuck")]
+        unsafe impl bytemuck::NoUninit for $name {}
+    };
+}
+
+ordered_float! {
+    /// An `f32` that is equal to itself and hashes consistently with its equality.
+    ///
+    /// `0.0` and `-0.0` are stored as `0.0`, and every NaN is stored as the same canonical NaN,
+    /// so two values are equal if and only if their bytes are equal.
+    /// This makes it safe to use in types hashed by their bytes, with any of the macros in this crate.
+    ///
+    /// With the `bytemuck` or `zerocopy` features enabled it implements `bytemuck::NoUninit`
+    /// or `zerocopy::IntoBytes` respectively. The traits that allow creating a value from
+    /// arbitrary bytes are deliberately not implemented, because they could create a non-canonical value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use derive_hash_fast::OrderedF32;
+    ///
+    /// assert_eq!(OrderedF32::new(0.0), OrderedF32::new(-0.0));
+    /// assert_eq!(OrderedF32::new(f32::NAN), OrderedF32::new(-f32::NAN));
+    /// assert!(OrderedF32::new(1.0) < OrderedF32::new(f32::NAN));
+    /// ```
+    OrderedF32, f32, u32, write_u32
+}
+
+ordered_float! {
+    /// An `f64` that is equal to itself and hashes consistently with its equality.
+    ///
+    /// `0.0` and `-0.0` are stored as `0.0`, and every NaN is stored as the same canonical NaN,
+    /// so two values are equal if and only if their bytes are equal.
+    /// This makes it safe to use in types hashed by their bytes, with any of the macros in this crate.
+    ///
+    /// With the `bytemuck` or `zerocopy` features enabled it implements `bytemuck::NoUninit`
+    /// or `zerocopy::IntoBytes` respectively. The traits that allow creating a value from
+    /// arbitrary bytes are deliberately not implemented, because they could create a non-canonical value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use derive_hash_fast::OrderedF64;
+    ///
+    /// assert_eq!(OrderedF64::new(0.0), OrderedF64::new(-0.0));
+    /// assert_eq!(OrderedF64::new(f64::NAN), OrderedF64::new(-f64::NAN));
+    /// assert!(OrderedF64::new(1.0) < OrderedF64::new(f64::NAN));
+    /// ```
+    OrderedF64, f64, u64, write_u64
+}
+
+/// Replaces `-0.0` with `0.0` and any NaN with the canonical NaN.
+pub(crate) trait Canonicalize {
+    fn canonicalize(self) -> Self;
+}
+
+macro_rules! impl_canonicalize {
+    ($($float:ty)*) => {
+        $(
+            impl Canonicalize for $float {
+                #[inline]
+                fn canonicalize(self) -> Self {
+                    if self.is_nan() {
+                        <$float>::NAN
+                    } else if self == 0.0 {
+                        0.0
+                    } else {
+                        self
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_canonicalize!(f32 f64);
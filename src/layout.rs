@@ -0,0 +1,149 @@
+use core::fmt;
+
+const SIZEOF_U128: usize = core::mem::size_of::<u128>();
+
+/// The width of a call to a [`Hasher`](core::hash::Hasher), named after the `write_*` method.
+///
+/// Signed integers are covered by the unsigned integers of the same width,
+/// because that is how `Hasher` forwards them unless a hasher overrides it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WriteWidth {
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    Usize,
+    /// The variable-length `Hasher::write`
+    Bytes,
+}
+
+impl WriteWidth {
+    /// Returns the name of the `Hasher` method, e.g. `"write_u64"`.
+    pub const fn method_name(self) -> &'static str {
+        match self {
+            WriteWidth::U8 => "write_u8",
+            WriteWidth::U16 => "write_u16",
+            WriteWidth::U32 => "write_u32",
+            WriteWidth::U64 => "write_u64",
+            WriteWidth::U128 => "write_u128",
+            WriteWidth::Usize => "write_usize",
+            WriteWidth::Bytes => "write",
+        }
+    }
+}
+
+/// The sequence of writes the default [`Optimal`](crate::Optimal) plan makes for a given number of bytes.
+///
+/// Describes the hash of a type hashed as its own bytes with the default plan, e.g. with
+/// [`derive_hash_fast_bytemuck!`](crate::derive_hash_fast_bytemuck). It does not describe types with
+/// another [`WritePlan`](crate::WritePlan), nor types hashed with
+/// [`derive_hash_fast_fields!`](crate::derive_hash_fast_fields), whose fields are packed into
+/// a buffer of the sum of their sizes: pass that sum to [`optimal_write_layout`](crate::optimal_write_layout) instead.
+///
+/// Every value of that many bytes is hashed with `u128_chunks` calls to `write_u128`, one for every
+/// full 16 bytes, followed by one `tail` write for the bytes that are left over, if any.
+/// The tail is zero-padded up to its width with `padding` bytes.
+///
+/// Obtained with [`WriteLayout::optimal_for_size_of`] or [`optimal_write_layout`](crate::optimal_write_layout), both of which
+/// can be evaluated at compile time, e.g. to enforce a size budget in a `const` assertion.
+/// The `Display` implementation describes the sequence for reports and code review,
+/// e.g. `2 × write_u128 + write_u64 (4 bytes of padding)` for a 36-byte type.
+///
+/// # Examples
+///
+/// ```
+/// use derive_hash_fast::{WriteLayout, WriteWidth};
+///
+/// #[repr(C)]
+/// struct Record {
+///     id: u32,
+///     payload: [u32; 8],
+/// }
+///
+/// const LAYOUT: WriteLayout = WriteLayout::optimal_for_size_of::<Record>();
+/// assert_eq!(LAYOUT.u128_chunks, 2);
+/// assert_eq!(LAYOUT.tail, Some(WriteWidth::U64));
+/// assert_eq!(LAYOUT.padding, 4);
+/// const _: () = assert!(LAYOUT.writes() <= 3, "Record is too large to hash cheaply");
+/// assert_eq!(LAYOUT.to_string(), "2 × write_u128 + write_u64 (4 bytes of padding)");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WriteLayout {
+    /// The number of `write_u128` calls for full 16-byte chunks.
+    pub u128_chunks: usize,
+    /// The write for the bytes after the last full chunk, if there are any.
+    pub tail: Option<WriteWidth>,
+    /// The number of zero bytes the tail is padded with.
+    pub padding: usize,
+}
+
+impl WriteLayout {
+    /// Returns the sequence of writes the [`Optimal`](crate::Optimal) plan makes for `size_of::<T>()` bytes,
+    /// the same as `optimal_write_layout(size_of::<T>())`.
+    pub const fn optimal_for_size_of<T>() -> Self {
+        optimal_write_layout(core::mem::size_of::<T>())
+    }
+
+    /// Returns the total number of calls to the hasher.
+    pub const fn writes(&self) -> usize {
+        self.u128_chunks + self.tail.is_some() as usize
+    }
+
+    /// Returns the widths of the calls to the hasher, in order.
+    pub fn widths(&self) -> impl Iterator<Item = WriteWidth> {
+        core::iter::repeat(WriteWidth::U128).take(self.u128_chunks).chain(self.tail)
+    }
+}
+
+impl fmt::Display for WriteLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.u128_chunks, self.tail) {
+            (0, None) => f.write_str("no writes")?,
+            (0, Some(tail)) => f.write_str(tail.method_name())?,
+            (chunks, None) => write!(f, "{chunks} × write_u128")?,
+            (chunks, Some(tail)) => write!(f, "{chunks} × write_u128 + {}", tail.method_name())?,
+        }
+        match self.padding {
+            0 => Ok(()),
+            1 => f.write_str(" (1 byte of padding)"),
+            padding => write!(f, " ({padding} bytes of padding)"),
+        }
+    }
+}
+
+/// Returns the sequence of writes the default [`Optimal`](crate::Optimal) plan makes for `size` bytes.
+///
+/// Makes the same decisions as [`hash_fixed_bytes`](crate::hash_fixed_bytes):
+/// values of up to 16 bytes get a single write of the next supported width,
+/// larger ones get `write_u128` chunks and a tail padded to a `u64` or a `u128`, whichever fits.
+/// See [`WriteLayout`] for the details.
+///
+/// # Examples
+///
+/// ```
+/// use derive_hash_fast::{optimal_write_layout, WriteWidth};
+///
+/// // 10 bytes are padded to a single `u128`
+/// assert_eq!(optimal_write_layout(10).tail, Some(WriteWidth::U128));
+/// assert_eq!(optimal_write_layout(10).padding, 6);
+/// // 40 bytes are two `u128`s and a `u64`, without padding
+/// assert_eq!(optimal_write_layout(40).to_string(), "2 × write_u128 + write_u64");
+/// ```
+pub const fn optimal_write_layout(size: usize) -> WriteLayout {
+    let (tail, padding) = match size {
+        0 => (None, 0),
+        1 => (Some(WriteWidth::U8), 0),
+        2 => (Some(WriteWidth::U16), 0),
+        3..=4 => (Some(WriteWidth::U32), 4 - size),
+        5..=8 => (Some(WriteWidth::U64), 8 - size),
+        9..=15 => (Some(WriteWidth::U128), 16 - size),
+        // `hash_padded_large`: full chunks, then the remainder padded to a u64 or a u128
+        _ => match size % SIZEOF_U128 {
+            0 => (None, 0),
+            remainder @ 1..=8 => (Some(WriteWidth::U64), 8 - remainder),
+            remainder => (Some(WriteWidth::U128), 16 - remainder),
+        },
+    };
+    WriteLayout { u128_chunks: size / SIZEOF_U128, tail, padding }
+}
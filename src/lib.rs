@@ -1,11 +1,20 @@
 #![no_std]
 
+#[cfg(any(test, feature = "testing"))]
+extern crate alloc;
+// `#[derive(arbitrary::Arbitrary)]` in the tests refers to `std` from the crate root,
+// and the `std` feature implements `std::error::Error`
+#[cfg(any(test, feature = "std"))]
+extern crate std;
+
 /// Derives a fast `Hash` implementation for `bytemuck` types.
 ///
 /// This macro implements [`core::hash::Hash`] by calling `.bytes_of()` on the
 /// struct, which leverages `bytemuck` to get a byte representation of the
 /// type for hashing.
 ///
+/// Equivalent to [`derive_hash_fast!(bytemuck, ...)`](crate::derive_hash_fast).
+///
 /// # Examples
 ///
 /// ```
@@ -29,22 +38,41 @@
 /// hashset.insert(MyStruct{a: true, b: 2, c: 3});
 /// assert!(hashset.contains(&MyStruct{a: true, b: 2, c: 3}));
 /// ```
+///
+/// # Enums
+///
+/// Fieldless enums with `#[repr(u8)]`, `#[repr(u16)]` or `#[repr(u32)]` can derive `NoUninit`
+/// and are hashed with a single `write_u8`, `write_u16` or `write_u32`.
+/// For enums that can't derive `NoUninit`, see [`derive_hash_fast_enum!`].
+///
+/// # Generic types
+///
+/// Generic types are supported with the `impl<...> for Type` syntax,
+/// which accepts arbitrary bounds and an optional `where` clause:
+///
+/// ```
+/// use derive_hash_fast::derive_hash_fast_bytemuck;
+/// use bytemuck::NoUninit;
+///
+/// #[repr(transparent)]
+/// #[derive(Eq, PartialEq, Clone, Copy)]
+/// struct Wrapper<T>(T);
+///
+/// // SAFETY: `Wrapper<T>` is `repr(transparent)` over `T`
+/// unsafe impl<T: NoUninit> NoUninit for Wrapper<T> {}
+///
+/// derive_hash_fast_bytemuck!(impl<T: NoUninit> for Wrapper<T>);
+///
+/// let mut hashset = std::collections::HashSet::new();
+/// hashset.insert(Wrapper(5u32));
+/// ```
 #[macro_export]
 macro_rules! derive_hash_fast_bytemuck {
-    ($T:ty) => {
-        impl core::hash::Hash for $T {
-            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
-                let bytes = ::bytemuck::bytes_of(self);
-                $crate::write_to_optimal_hasher_function::<{core::mem::size_of::<$T>()}>(bytes, state);
-            }
-
-            fn hash_slice<H: core::hash::Hasher>(data: &[Self], state: &mut H)
-            where
-                Self: Sized,
-            {
-                state.write(::bytemuck::cast_slice(data));
-            }
-        }
+    (plan = $plan:ty, $($input:tt)*) => {
+        $crate::derive_hash_fast!(plan = $plan, bytemuck, $($input)*);
+    };
+    ($($input:tt)*) => {
+        $crate::derive_hash_fast!(bytemuck, $($input)*);
     };
 }
 
@@ -54,6 +82,8 @@ macro_rules! derive_hash_fast_bytemuck {
 /// struct, which leverages `zerocopy` to get a byte representation of the
 /// type for hashing.
 ///
+/// Equivalent to [`derive_hash_fast!(zerocopy, ...)`](crate::derive_hash_fast).
+///
 /// # Examples
 ///
 /// ```
@@ -76,76 +106,650 @@ macro_rules! derive_hash_fast_bytemuck {
 /// hashset.insert(MyStruct{a: true, b: 2, c: 3});
 /// assert!(hashset.contains(&MyStruct{a: true, b: 2, c: 3}));
 /// ```
+///
+/// # Enums
+///
+/// Fieldless enums with `#[repr(u8)]`, `#[repr(u16)]` or `#[repr(u32)]` can derive `IntoBytes`
+/// and are hashed with a single `write_u8`, `write_u16` or `write_u32`.
+/// For enums that can't derive `IntoBytes`, see [`derive_hash_fast_enum!`].
+///
+/// # Generic types
+///
+/// Generic types are supported with the `impl<...> for Type` syntax,
+/// which accepts arbitrary bounds, const generics and an optional `where` clause:
+///
+/// ```
+/// use derive_hash_fast::derive_hash_fast_zerocopy;
+/// use zerocopy::{Immutable, IntoBytes};
+///
+/// #[repr(C)]
+/// #[derive(Eq, PartialEq, Immutable, IntoBytes)]
+/// struct Grid<T, const N: usize> {
+///     cells: [T; N],
+/// }
+///
+/// derive_hash_fast_zerocopy!(impl<T, const N: usize> for Grid<T, N> where T: IntoBytes + Immutable);
+///
+/// let mut hashset = std::collections::HashSet::new();
+/// hashset.insert(Grid { cells: [1u16, 2, 3] });
+/// ```
 #[macro_export]
 macro_rules! derive_hash_fast_zerocopy {
-    ($T:ty) => {
-        impl core::hash::Hash for $T {
-            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
-                let bytes = ::zerocopy::IntoBytes::as_bytes(self);
-                $crate::write_to_optimal_hasher_function::<{core::mem::size_of::<$T>()}>(bytes, state);
+    (plan = $plan:ty, $($input:tt)*) => {
+        $crate::derive_hash_fast!(plan = $plan, zerocopy, $($input)*);
+    };
+    ($($input:tt)*) => {
+        $crate::derive_hash_fast!(zerocopy, $($input)*);
+    };
+}
+
+/// Derives a fast `Hash` implementation from an explicit list of fields.
+///
+/// Unlike [`derive_hash_fast_bytemuck!`] and [`derive_hash_fast_zerocopy!`],
+/// this works for types with padding, such as many `#[repr(C)]` structs generated by `bindgen`.
+/// The listed fields are copied into a stack buffer without any padding between them,
+/// and the buffer is then hashed with the same fixed sequence of primitive writes
+/// that the other macros use. The size of the buffer is known at compile time.
+///
+/// Every field must implement [`HashField`]. Only the listed fields are hashed,
+/// so make sure your `PartialEq` implementation compares the same fields.
+///
+/// # Examples
+///
+/// ```
+/// use derive_hash_fast::derive_hash_fast_fields;
+///
+/// // 12 bytes in memory, 5 of which are padding
+/// #[repr(C)]
+/// #[derive(Eq, PartialEq)]
+/// struct MyStruct {
+///     a: u8,
+///     b: u32,
+///     c: u16,
+/// }
+///
+/// // Hashed as 7 bytes, padded to a single `write_u64`
+/// derive_hash_fast_fields!(MyStruct { a, b, c });
+///
+/// // Use the struct in a HashSet
+/// let mut hashset = std::collections::HashSet::new();
+/// hashset.insert(MyStruct{a: 1, b: 2, c: 3});
+/// assert!(hashset.contains(&MyStruct{a: 1, b: 2, c: 3}));
+/// ```
+///
+/// Tuple structs are supported by listing field indices, e.g. `derive_hash_fast_fields!(MyTuple { 0, 1 })`.
+///
+/// # Floats
+///
+/// Floats are hashed by their bytes, so `0.0` and `-0.0` hash differently, and so do NaNs
+/// with different payloads. If your `PartialEq` treats those as equal, use
+/// `derive_hash_fast_fields!(canonical_floats, MyType { a, b, c })`, which replaces
+/// `-0.0` with `0.0` and every NaN with the same canonical NaN while copying the fields.
+/// The result is still hashed with a single fixed sequence of primitive writes.
+///
+/// ```
+/// use derive_hash_fast::derive_hash_fast_fields;
+///
+/// struct Point {
+///     x: f32,
+///     y: f32,
+/// }
+///
+/// impl PartialEq for Point {
+///     fn eq(&self, other: &Self) -> bool {
+///         let same = |a: f32, b: f32| a == b || (a.is_nan() && b.is_nan());
+///         same(self.x, other.x) && same(self.y, other.y)
+///     }
+/// }
+///
+/// impl Eq for Point {}
+///
+/// derive_hash_fast_fields!(canonical_floats, Point { x, y });
+///
+/// let mut hashset = std::collections::HashSet::new();
+/// hashset.insert(Point { x: 0.0, y: f32::NAN });
+/// assert!(hashset.contains(&Point { x: -0.0, y: -f32::NAN }));
+/// ```
+///
+/// Alternatively, store floats as [`OrderedF32`] or [`OrderedF64`], which are canonicalized
+/// on construction and work with every macro in this crate.
+///
+/// # Portability
+///
+/// By default the fields are copied in native byte order, so hashes differ between
+/// little- and big-endian targets. `derive_hash_fast_fields!(portable, MyType { a, b, c })`
+/// copies them in little-endian byte order and hashes the result with
+/// [`hash_fixed_bytes_portable`], so the same value hashes the same on every target,
+/// given a hasher that does the same. This is free on little-endian targets.
+///
+/// Portable mode also widens `usize` and `isize` fields to 64 bits, so that 32-bit
+/// and 64-bit targets agree on both the bytes and the hasher writes they are split into.
+///
+/// ```
+/// use derive_hash_fast::derive_hash_fast_fields;
+///
+/// #[derive(Eq, PartialEq)]
+/// struct ShardKey {
+///     tenant: u32,
+///     bucket: u16,
+/// }
+///
+/// derive_hash_fast_fields!(portable, ShardKey { tenant, bucket });
+/// ```
+///
+/// # Write plans
+///
+/// A write plan other than [`Optimal`] is selected with a `plan = ...,` prefix before everything else,
+/// e.g. `derive_hash_fast_fields!(plan = U64Only, portable, MyType { a, b, c })`. See [`WritePlan`].
+#[macro_export]
+macro_rules! derive_hash_fast_fields {
+    (@impl $size:ident $write:ident $hash:ident $plan:ty, $T:ty { $($field:tt),+ }) => {
+        impl ::core::hash::Hash for $T {
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                const PACKED_SIZE: usize = 0 $(+ $crate::$size(|s: &$T| &s.$field))+;
+                let mut buffer = [0u8; PACKED_SIZE];
+                let mut offset = 0;
+                $(
+                    offset = $crate::$write(&self.$field, &mut buffer, offset);
+                )+
+                ::core::debug_assert_eq!(offset, PACKED_SIZE);
+                <$plan as $crate::WritePlan>::$hash(&buffer, state);
+            }
+        }
+    };
+    (@mode $plan:ty, canonical_floats, $T:ty { $($field:tt),+ $(,)? }) => {
+        $crate::derive_hash_fast_fields!(@impl packed_field_size write_canonical_packed_field hash_fixed_bytes $plan, $T { $($field),+ });
+    };
+    (@mode $plan:ty, portable, $T:ty { $($field:tt),+ $(,)? }) => {
+        $crate::derive_hash_fast_fields!(@impl packed_portable_field_size write_portable_packed_field hash_fixed_bytes_portable $plan, $T { $($field),+ });
+    };
+    (@mode $plan:ty, $T:ty { $($field:tt),+ $(,)? }) => {
+        $crate::derive_hash_fast_fields!(@impl packed_field_size write_packed_field hash_fixed_bytes $plan, $T { $($field),+ });
+    };
+    (plan = $plan:ty, $($input:tt)*) => {
+        $crate::derive_hash_fast_fields!(@mode $plan, $($input)*);
+    };
+    ($($input:tt)*) => {
+        $crate::derive_hash_fast_fields!(@mode $crate::Optimal, $($input)*);
+    };
+}
+
+/// Derives a fast `Hash` implementation for enums.
+///
+/// # Fieldless enums
+///
+/// `derive_hash_fast_enum!(MyEnum as u8)` is a fallback for fieldless enums that can't derive
+/// `bytemuck::NoUninit` or `zerocopy::IntoBytes`, for example because they have no `#[repr]` attribute.
+/// The enum is cast to the given integer type with `as` and hashed with a single write of that width.
+/// The enum must implement `Copy`, and must not be larger than the integer type:
+/// a narrower integer would truncate the discriminants, so that different variants hash the same.
+/// This is checked at compile time.
+///
+/// This also implements [`HashField`], so the enum can be a field of a type
+/// hashed with [`derive_hash_fast_fields!`].
+///
+/// ```
+/// use derive_hash_fast::derive_hash_fast_enum;
+///
+/// #[derive(Eq, PartialEq, Clone, Copy)]
+/// enum Direction {
+///     North,
+///     East,
+///     South,
+///     West,
+/// }
+///
+/// // Hashed with a single `write_u8`
+/// derive_hash_fast_enum!(Direction as u8);
+///
+/// let mut hashset = std::collections::HashSet::new();
+/// hashset.insert(Direction::North);
+/// assert!(hashset.contains(&Direction::North));
+/// ```
+///
+/// An integer type that can't hold every discriminant fails to compile:
+///
+/// ```compile_fail
+/// use derive_hash_fast::derive_hash_fast_enum;
+///
+/// #[derive(Clone, Copy)]
+/// enum Large {
+///     A = 1,
+///     B = 257,
+/// }
+///
+/// derive_hash_fast_enum!(Large as u8);
+/// ```
+///
+/// # Enums with fields
+///
+/// Enums with fields, such as `#[repr(C, u8)]` enums with payloads of different sizes,
+/// have padding in the shorter variants and can't be viewed as bytes.
+/// `derive_hash_fast_enum!(MyEnum { VariantA { x, y }, VariantB(a, b), VariantC })`
+/// lists every variant with bindings for its fields. Only the active variant is hashed:
+/// a one-byte tag identifying the variant, followed by the variant's fields without padding,
+/// as a single fixed sequence of primitive writes chosen for that variant's size.
+/// The tag is the position of the variant in the list, so up to 256 variants are supported.
+///
+/// Every field must implement [`HashField`] and `Copy`.
+///
+/// ```
+/// use derive_hash_fast::derive_hash_fast_enum;
+///
+/// #[repr(C, u8)]
+/// #[derive(Eq, PartialEq)]
+/// enum Event {
+///     Click { x: u16, y: u16 },
+///     Key(u32, bool),
+///     Quit,
+/// }
+///
+/// // `Click` is hashed with a single `write_u64`, `Quit` with a single `write_u8`
+/// derive_hash_fast_enum!(Event { Click { x, y }, Key(code, pressed), Quit });
+///
+/// let mut hashset = std::collections::HashSet::new();
+/// hashset.insert(Event::Click { x: 1, y: 2 });
+/// assert!(hashset.contains(&Event::Click { x: 1, y: 2 }));
+/// assert!(!hashset.contains(&Event::Quit));
+/// ```
+#[macro_export]
+macro_rules! derive_hash_fast_enum {
+    ($T:ty as $Repr:ty) => {
+        const _: () = ::core::assert!(
+            ::core::mem::size_of::<$T>() <= ::core::mem::size_of::<$Repr>(),
+            "the integer type is narrower than the enum and would truncate its discriminants",
+        );
+
+        impl ::core::hash::Hash for $T {
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                let bytes = (*self as $Repr).to_ne_bytes();
+                $crate::hash_fixed_bytes(&bytes, state);
+            }
+        }
+
+        impl $crate::HashField for $T {
+            const SIZE: usize = ::core::mem::size_of::<$Repr>();
+            const PORTABLE_SIZE: usize = <$Repr as $crate::HashField>::PORTABLE_SIZE;
+
+            #[inline]
+            fn write_bytes(&self, out: &mut [u8]) {
+                out.copy_from_slice(&(*self as $Repr).to_ne_bytes());
+            }
+
+            #[inline]
+            fn write_portable_bytes(&self, out: &mut [u8]) {
+                $crate::HashField::write_portable_bytes(&(*self as $Repr), out);
             }
+        }
+    };
+    ($T:ty { $($Variant:ident $({ $($field:ident),* $(,)? })? $(( $($binding:ident),* $(,)? ))?),+ $(,)? }) => {
+        impl ::core::hash::Hash for $T {
+            // unit variants have an empty payload, `()`
+            #[allow(clippy::unused_unit)]
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                // assigns a tag to every variant, and fails to compile if there are more than 256
+                #[allow(dead_code)]
+                #[repr(u8)]
+                enum Tag {
+                    $($Variant),+
+                }
+                // `Self` and qualified paths can't be used in the patterns below
+                type Enum = $T;
 
-            fn hash_slice<H: core::hash::Hasher>(data: &[Self], state: &mut H)
-            where
-                Self: Sized,
-            {
-                state.write(::zerocopy::transmute_ref!(data));
+                match self {
+                    $(
+                        Enum::$Variant $({ $($field),* })? $(( $($binding),* ))? => {
+                            const PACKED_SIZE: usize = 1 + $crate::packed_size_of(|value: &Enum| match value {
+                                Enum::$Variant $({ $($field),* })? $(( $($binding),* ))? => {
+                                    ($($(*$field,)*)? $($(*$binding,)*)?)
+                                }
+                                #[allow(unreachable_patterns)]
+                                _ => ::core::unreachable!(),
+                            });
+                            let mut buffer = [0u8; PACKED_SIZE];
+                            buffer[0] = Tag::$Variant as u8;
+                            $crate::HashField::write_bytes(
+                                &($($(*$field,)*)? $($(*$binding,)*)?),
+                                &mut buffer[1..],
+                            );
+                            $crate::hash_fixed_bytes(&buffer, state);
+                        }
+                    )+
+                }
             }
         }
     };
 }
 
+/// Splits `impl<...> for Type where ...` into its parts and passes them on to
+/// `$crate::$callback!(@impl {args} [generics] [Type] [where clauses])`,
+/// where `{args}` are passed through unchanged and can be empty.
+///
+/// The generic parameters can contain arbitrary bounds, so they are collected
+/// token by token until the `>` that is immediately followed by `for`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __split_impl_generics {
+    ($callback:ident {$($args:tt)*} [$($generics:tt)*] > for $T:ty $(where $($where:tt)+)?) => {
+        $crate::$callback!(@impl $($args)* [$($generics)*] [$T] [$($($where)+)?]);
+    };
+    // `Bound<T>> for` is lexed as a single `>>` token
+    ($callback:ident {$($args:tt)*} [$($generics:tt)*] >> for $T:ty $(where $($where:tt)+)?) => {
+        $crate::$callback!(@impl $($args)* [$($generics)* >] [$T] [$($($where)+)?]);
+    };
+    ($callback:ident {$($args:tt)*} [$($generics:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::__split_impl_generics!($callback {$($args)*} [$($generics)* $next] $($rest)*);
+    };
+}
+
+/// Expands to a path into `bytemuck`, e.g. `$crate::__bytemuck!(bytes_of(self))`.
+///
+/// Resolves to the copy re-exported in `__private` when the `bytemuck` feature is enabled,
+/// so the macros work even if the user's crate renames the dependency or re-exports it
+/// through a facade crate. Falls back to the user's own `bytemuck` otherwise.
+#[cfg(feature = "bytemuck")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __bytemuck {
+    ($($path:tt)*) => { $crate::__private::bytemuck::$($path)* };
+}
+
+#[cfg(not(feature = "bytemuck"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __bytemuck {
+    ($($path:tt)*) => { ::bytemuck::$($path)* };
+}
+
+/// Expands to a path into `zerocopy`, e.g. `$crate::__zerocopy!(IntoBytes::as_bytes(self))`.
+///
+/// Same as [`__bytemuck!`], for the `zerocopy` feature.
+#[cfg(feature = "zerocopy")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __zerocopy {
+    ($($path:tt)*) => { $crate::__private::zerocopy::$($path)* };
+}
+
+#[cfg(not(feature = "zerocopy"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __zerocopy {
+    ($($path:tt)*) => { ::zerocopy::$($path)* };
+}
+
+/// Dependencies re-exported for use in macro expansions. Not public API.
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "bytemuck")]
+    pub use bytemuck;
+    #[cfg(feature = "zerocopy")]
+    pub use zerocopy;
+}
+
+#[cfg(feature = "derive")]
+pub use derive_hash_fast_derive::HashFast;
+
+mod array;
+pub use array::FastArray;
+
+mod as_bytes;
+mod byte_order;
+pub use as_bytes::AsHashBytes;
+
+mod coalescing;
+pub use coalescing::{CoalescingBuildHasher, CoalescingHasher};
+#[doc(hidden)]
+pub use as_bytes::{
+    hash_as_bytes, hash_as_bytes_portable, hash_slice_as_bytes, hash_slice_as_bytes_portable, raw_field_size,
+};
+
+#[cfg(any(test, feature = "testing"))]
+mod consistency;
+#[cfg(any(test, feature = "testing"))]
+pub use consistency::{check_hash_eq_consistency, HashEqInconsistency};
+
+mod eq;
+#[doc(hidden)]
+pub use eq::bytes_eq_for;
+
+mod ord;
+#[doc(hidden)]
+pub use ord::{bytes_cmp_for, bytes_cmp_lexicographic_for};
+
+mod fields;
+pub use fields::HashField;
+#[doc(hidden)]
+pub use fields::{
+    packed_field_size, packed_portable_field_size, packed_size_of, write_canonical_packed_field, write_packed_field, write_portable_packed_field,
+};
+
+#[cfg(feature = "fixed-hasher")]
+mod fixed;
+#[cfg(feature = "fixed-hasher")]
+pub use fixed::{FixedBuildHasher, FixedHasher};
+
+mod float;
+pub use float::{OrderedF32, OrderedF64};
+
+mod layout;
+pub use layout::{optimal_write_layout, WriteLayout, WriteWidth};
+
+mod plan;
+pub use plan::{Optimal, OverlappingTail, SingleWrite, U128Preferred, U64Only, WritePlan};
+
+#[cfg(any(test, feature = "testing"))]
+mod recording;
+#[cfg(any(test, feature = "testing"))]
+pub use recording::{RecordedWrite, RecordingHasher};
+
+mod wrapper;
+#[cfg(feature = "bytemuck")]
+pub use wrapper::Bytemuck;
+#[cfg(feature = "zerocopy")]
+pub use wrapper::Zerocopy;
+pub use wrapper::HashAsBytes;
+
 use core::hash::Hasher;
 
+use byte_order::{ByteOrder, LittleEndian, NativeEndian};
+
+/// Hashes a fixed-size byte array with the same sequence of primitive writes
+/// that the macros in this crate use for a type of size `N`.
+///
+/// Instead of the variable-length `Hasher::write`, the bytes are fed to the hasher
+/// through `write_u8`, `write_u16`, `write_u32`, `write_u64` or `write_u128`,
+/// zero-padded to the next supported width. Types larger than 16 bytes are split into
+/// `u128` chunks, with the last one padded to either a `u64` or a `u128`.
+/// The sequence is chosen at compile time and has no runtime overhead.
+///
+/// Use this for hand-written `Hash` implementations. Unlike the helpers the macros
+/// expand to, this function is part of the public API and follows semver.
+///
+/// # Examples
+///
+/// ```
+/// use derive_hash_fast::{hash_fixed_bytes, hash_slice_bytes};
+/// use std::hash::{Hash, Hasher};
+///
+/// #[repr(transparent)]
+/// #[derive(Clone, Copy, bytemuck::NoUninit)]
+/// struct Digest([u8; 20]);
+///
+/// impl Hash for Digest {
+///     fn hash<H: Hasher>(&self, state: &mut H) {
+///         // one write_u128 and one write_u64
+///         hash_fixed_bytes::<20>(&self.0, state);
+///     }
+///
+///     fn hash_slice<H: Hasher>(data: &[Self], state: &mut H) {
+///         // the digests are contiguous in memory, so the slice can be hashed in place
+///         hash_slice_bytes(bytemuck::cast_slice(data), state);
+///     }
+/// }
+/// ```
+#[inline]
+pub fn hash_fixed_bytes<const N: usize>(bytes: &[u8; N], state: &mut impl Hasher) {
+    write_bytes_of_constant_size::<NativeEndian>(bytes, state)
+}
+
+/// Same as [`hash_fixed_bytes`], but the result does not depend on the endianness of the target.
+///
+/// [`hash_fixed_bytes`] loads the bytes into integers in native byte order, so the same bytes
+/// produce different integers, and therefore different hashes, on little- and big-endian targets.
+/// This function always loads them in little-endian order. The sequence of writes is the same,
+/// and on little-endian targets so are the integers, so there is no runtime overhead on those.
+///
+/// This only makes the hash portable if the bytes themselves are, i.e. multi-byte integers
+/// are stored in a fixed byte order, and if the hasher hashes integers the same way on every target.
+///
+/// # Examples
+///
+/// ```
+/// use derive_hash_fast::hash_fixed_bytes_portable;
+/// use std::hash::{Hash, Hasher};
+///
+/// struct Version {
+///     major: u16,
+///     minor: u16,
+/// }
+///
+/// impl Hash for Version {
+///     fn hash<H: Hasher>(&self, state: &mut H) {
+///         let mut bytes = [0u8; 4];
+///         bytes[..2].copy_from_slice(&self.major.to_le_bytes());
+///         bytes[2..].copy_from_slice(&self.minor.to_le_bytes());
+///         // `write_u32(major | minor << 16)` on every target
+///         hash_fixed_bytes_portable(&bytes, state);
+///     }
+/// }
+/// ```
+#[inline]
+pub fn hash_fixed_bytes_portable<const N: usize>(bytes: &[u8; N], state: &mut impl Hasher) {
+    write_bytes_of_constant_size::<LittleEndian>(bytes, state)
+}
+
+/// Hashes the bytes of a slice of values, the way the `hash_slice` implementations
+/// generated by the macros in this crate do.
+///
+/// Like [`Hash::hash_slice`](core::hash::Hash::hash_slice), this does not write the length of the slice:
+/// [`Hash`](core::hash::Hash) for `[T]` writes the length prefix before calling `hash_slice`.
+/// Call this from `hash_slice` with the bytes of the whole slice,
+/// and [`hash_fixed_bytes`] from `hash` with the bytes of a single value.
+///
+/// Up to 16 bytes are zero-padded and hashed with a single write of the same width as [`hash_fixed_bytes`]
+/// would use for that many bytes, since hashers with a fast path for fixed-size integers are often slow
+/// for short variable-length writes. Longer slices go through a single `Hasher::write`.
+/// The padding doesn't cause collisions because the length prefix written before `hash_slice`
+/// determines the number of bytes.
+#[inline]
+pub fn hash_slice_bytes(bytes: &[u8], state: &mut impl Hasher) {
+    hash_slice_bytes_in::<NativeEndian>(bytes, state)
+}
+
+/// Same as [`hash_slice_bytes`], but the result does not depend on the endianness of the target,
+/// see [`hash_fixed_bytes_portable`].
+#[inline]
+pub fn hash_slice_bytes_portable(bytes: &[u8], state: &mut impl Hasher) {
+    hash_slice_bytes_in::<LittleEndian>(bytes, state)
+}
+
+#[inline(always)]
+fn hash_slice_bytes_in<O: ByteOrder>(bytes: &[u8], state: &mut impl Hasher) {
+    // The same widths and zero-padded values as `write_bytes_of_constant_size`.
+    // Copying into a zeroed buffer would be a `memcpy` call with a runtime length,
+    // which costs more than hashing a short slice, so the padded integer is assembled
+    // from two loads that overlap when the length is not a power of two.
+    let len = bytes.len();
+    match len {
+        0 => (),
+        1 => state.write_u8(bytes[0]),
+        2 => state.write_u16(O::u16([bytes[0], bytes[1]])),
+        3 => state.write_u32(O::u32([bytes[0], bytes[1], bytes[2], 0])),
+        4 => state.write_u32(O::u32(bytes.try_into().unwrap())),
+        5..=8 => {
+            let lo = u32::from_le_bytes(bytes[..4].try_into().unwrap());
+            let hi = u32::from_le_bytes(bytes[len - 4..].try_into().unwrap());
+            let padded = u64::from(lo) | u64::from(hi) << (8 * (len - 4));
+            state.write_u64(O::u64(padded.to_le_bytes()))
+        }
+        9..=16 => {
+            let lo = u64::from_le_bytes(bytes[..8].try_into().unwrap());
+            let hi = u64::from_le_bytes(bytes[len - 8..].try_into().unwrap());
+            let padded = u128::from(lo) | u128::from(hi) << (8 * (len - 8));
+            state.write_u128(O::u128(padded.to_le_bytes()))
+        }
+        _ => write_long_slice(bytes, state),
+    }
+}
+
+/// Kept out of line, so that the short lengths above don't pay for saving the registers
+/// that an inlined variable-length `write` needs. It hashes more than 16 bytes, which dwarfs the call.
+#[inline(never)]
+fn write_long_slice(bytes: &[u8], state: &mut impl Hasher) {
+    state.write(bytes)
+}
+
+/// Prefer [`hash_fixed_bytes`], which checks the length at compile time.
 #[doc(hidden)]
 #[inline]
 pub fn write_to_optimal_hasher_function<const B: usize>(bytes: &[u8], state: &mut impl Hasher) {
     assert!(bytes.len() == B);
+    write_bytes_of_constant_size::<NativeEndian>(bytes, state)
+}
+
+/// The length of `bytes` must be a compile-time constant at every call site,
+/// which is why this is `#[inline(always)]`.
+#[inline(always)]
+fn write_bytes_of_constant_size<O: ByteOrder>(bytes: &[u8], state: &mut impl Hasher) {
     // Dispatch to a specialized hashing function for the struct's size, if one is available.
     // This match incurs no runtime overhead in release mode because it matches on a constant.
-    match B {
-        1 => state.write_u8(u8::from_ne_bytes(bytes.try_into().unwrap())),
-        2 => state.write_u16(u16::from_ne_bytes(bytes.try_into().unwrap())),
-        3 => state.write_u32(pad_to_u32::<3>(bytes.try_into().unwrap())),
-        4 => state.write_u32(u32::from_ne_bytes(bytes.try_into().unwrap())),
-        5..=7 => state.write_u64(pad_to_u64::<B>(bytes.try_into().unwrap())),
-        8 => state.write_u64(u64::from_ne_bytes(bytes.try_into().unwrap())),
-        9..=15 => state.write_u128(pad_to_u128::<B>(bytes.try_into().unwrap())),
-        16 => state.write_u128(u128::from_ne_bytes(bytes.try_into().unwrap())),
-        17..=64 => hash_padded_large::<B>(bytes.try_into().unwrap(), state),
-        // TODO: const generic optimiation to lower into several u128 writes with the final one padded
-        _ => state.write(bytes),
+    match bytes.len() {
+        1 => state.write_u8(bytes[0]),
+        2 => state.write_u16(O::u16(bytes.try_into().unwrap())),
+        3 => state.write_u32(pad_to_u32::<O>(bytes)),
+        4 => state.write_u32(O::u32(bytes.try_into().unwrap())),
+        5..=7 => state.write_u64(pad_to_u64::<O>(bytes)),
+        8 => state.write_u64(O::u64(bytes.try_into().unwrap())),
+        9..=15 => state.write_u128(pad_to_u128::<O>(bytes)),
+        16 => state.write_u128(O::u128(bytes.try_into().unwrap())),
+        17.. => hash_padded_large::<O>(bytes, state),
+        // zero-sized types: nothing to hash, same as #[derive(Hash)]
+        0 => (),
     }
 }
 
 #[inline]
-fn pad_to_u32<const N: usize>(bytes: &[u8; N]) -> u32 {
+fn pad_to_u32<O: ByteOrder>(bytes: &[u8]) -> u32 {
     let mut padded_bytes = [0u8; core::mem::size_of::<u32>()];
-    padded_bytes[..N].copy_from_slice(bytes);
-    u32::from_ne_bytes(padded_bytes.try_into().unwrap())
+    padded_bytes[..bytes.len()].copy_from_slice(bytes);
+    O::u32(padded_bytes)
 }
 
 #[inline]
-fn pad_to_u64<const N: usize>(bytes: &[u8; N]) -> u64 {
+fn pad_to_u64<O: ByteOrder>(bytes: &[u8]) -> u64 {
     let mut padded_bytes = [0u8; core::mem::size_of::<u64>()];
-    padded_bytes[..N].copy_from_slice(bytes);
-    u64::from_ne_bytes(padded_bytes.try_into().unwrap())
+    padded_bytes[..bytes.len()].copy_from_slice(bytes);
+    O::u64(padded_bytes)
 }
 
 #[inline]
-fn pad_to_u128<const N: usize>(bytes: &[u8; N]) -> u128 {
+fn pad_to_u128<O: ByteOrder>(bytes: &[u8]) -> u128 {
     let mut padded_bytes = [0u8; core::mem::size_of::<u128>()];
-    padded_bytes[..N].copy_from_slice(bytes);
-    u128::from_ne_bytes(padded_bytes.try_into().unwrap())
+    padded_bytes[..bytes.len()].copy_from_slice(bytes);
+    O::u128(padded_bytes)
 }
 
-#[inline]
-fn hash_padded_large<const N: usize>(bytes: &[u8; N], state: &mut impl Hasher) {
+/// Lowers a large struct into a sequence of `write_u128` calls, with the final one padded.
+/// The length is a constant after inlining, so the number of full chunks and the shape
+/// of the tail are known at compile time and the loop is fully unrolled for small sizes.
+#[inline(always)]
+fn hash_padded_large<O: ByteOrder>(bytes: &[u8], state: &mut impl Hasher) {
     const SIZEOF_U128: usize = core::mem::size_of::<u128>();
     let chunks_iter = bytes.chunks_exact(SIZEOF_U128);
     let remainder = chunks_iter.remainder();
     for chunk in chunks_iter {
-        state.write_u128(u128::from_ne_bytes(chunk.try_into().unwrap()))
+        state.write_u128(O::u128(chunk.try_into().unwrap()))
     }
     // pad to either u64 or u128 to limit the amount of extra work performed
     // compared to always padding to u128.
@@ -154,17 +758,9 @@ fn hash_padded_large<const N: usize>(bytes: &[u8; N], state: &mut impl Hasher) {
     // and only helps really naive implementations like std::DefaultHasher
     match remainder.len() {
         0 => (), // nothing to do
-        1..=7 => {
-            let mut padded_bytes = [0u8; core::mem::size_of::<u64>()];
-            padded_bytes[..remainder.len()].copy_from_slice(remainder);
-            state.write_u64(u64::from_ne_bytes(padded_bytes.try_into().unwrap()))
-        }
-        8 => state.write_u64(u64::from_ne_bytes(remainder.try_into().unwrap())),
-        9..=15 => {
-            let mut padded_bytes = [0u8; core::mem::size_of::<u128>()];
-            padded_bytes[..remainder.len()].copy_from_slice(remainder);
-            state.write_u128(u128::from_ne_bytes(padded_bytes.try_into().unwrap()))
-        },
+        1..=7 => state.write_u64(pad_to_u64::<O>(remainder)),
+        8 => state.write_u64(O::u64(remainder.try_into().unwrap())),
+        9..=15 => state.write_u128(pad_to_u128::<O>(remainder)),
         SIZEOF_U128.. => unreachable!(),
     }
 }
@@ -172,7 +768,10 @@ fn hash_padded_large<const N: usize>(bytes: &[u8; N], state: &mut impl Hasher) {
 #[cfg(test)]
 mod tests {
     extern crate std;
-    use super::*;
+    use super::{OrderedF32, OrderedF64};
+    use crate::WriteWidth::{U128, U16, U32, U64, U8};
+    use crate::{RecordedWrite, RecordingHasher, WriteWidth};
+    use std::vec::Vec;
 
     #[repr(C)]
     #[derive(Clone, Copy, bytemuck::NoUninit)]
@@ -212,6 +811,7 @@ mod tests {
         assert_ne!(hash_1, hash_2);
     }
 
+    #[allow(dead_code)] // only read through the byte view
     #[derive(Clone, zerocopy::Immutable, zerocopy::IntoBytes)]
     struct FooZ {
         a: u16,
@@ -248,4 +848,1084 @@ mod tests {
         assert_eq!(hash_1, hash_1_again);
         assert_ne!(hash_1, hash_2);
     }
+
+    #[repr(C)]
+    #[derive(Clone, Copy, bytemuck::NoUninit)]
+    struct Large {
+        a: [u32; 24],
+        b: u32,
+    }
+
+    derive_hash_fast_bytemuck!(Large);
+
+    #[test]
+    fn test_large_struct_lowering() {
+        use std::hash::Hash;
+        let mut recorder = RecordingHasher::new();
+        Large { a: [7; 24], b: 3 }.hash(&mut recorder);
+        // 100 bytes: six full u128 writes and a u64 padded from the 4 remaining bytes
+        assert_eq!(recorder.widths(), [U128, U128, U128, U128, U128, U128, U64]);
+    }
+
+    #[repr(transparent)]
+    #[derive(zerocopy::Immutable, zerocopy::IntoBytes)]
+    struct GenericZ<T>(T);
+
+    derive_hash_fast_zerocopy!(impl<T: zerocopy::IntoBytes + zerocopy::Immutable + Into<u64>> for GenericZ<T>);
+
+    #[test]
+    fn test_generic_dispatch_per_instantiation() {
+        use std::hash::Hash;
+        let mut recorder = RecordingHasher::new();
+        GenericZ(5u64).hash(&mut recorder);
+        GenericZ(5u32).hash(&mut recorder);
+        assert_eq!(recorder.widths(), [U64, U32]);
+    }
+
+    #[repr(C)]
+    struct Padded {
+        a: u8,
+        b: u32,
+        c: u16,
+    }
+
+    derive_hash_fast_fields!(Padded { a, b, c });
+
+    #[repr(C)]
+    struct PaddedTuple(u16, u64);
+
+    derive_hash_fast_fields!(PaddedTuple { 0, 1 });
+
+    #[test]
+    fn test_fields() {
+        use std::hash::Hash;
+        let mut recorder = RecordingHasher::new();
+        Padded { a: 1, b: 2, c: 3 }.hash(&mut recorder);
+        PaddedTuple(1, 2).hash(&mut recorder);
+        // 7 bytes padded to a u64, then 10 bytes padded to a u128
+        assert_eq!(recorder.widths(), [U64, U128]);
+
+        let hash = |val: &Padded| {
+            use std::hash::{DefaultHasher, Hasher};
+            let mut hasher = DefaultHasher::new();
+            val.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&Padded { a: 1, b: 2, c: 3 }), hash(&Padded { a: 1, b: 2, c: 3 }));
+        assert_ne!(hash(&Padded { a: 1, b: 2, c: 3 }), hash(&Padded { a: 1, b: 3, c: 2 }));
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, bytemuck::NoUninit)]
+    struct LargeEq {
+        a: [u32; 5],
+        b: u8,
+        c: [u8; 3],
+    }
+
+    crate::derive_hash_eq_fast_bytemuck!(LargeEq);
+
+    #[repr(transparent)]
+    #[derive(Debug, zerocopy::Immutable, zerocopy::IntoBytes)]
+    struct GenericEqZ<T>(T);
+
+    crate::derive_eq_fast_zerocopy!(impl<T: zerocopy::IntoBytes + zerocopy::Immutable> for GenericEqZ<T>);
+
+    #[test]
+    fn test_eq() {
+        let large = LargeEq { a: [1, 2, 3, 4, 5], b: 6, c: [7, 8, 9] };
+        assert_eq!(large, large);
+        // differences in the full u128 chunk and in the padded tail
+        assert_ne!(large, LargeEq { a: [0, 2, 3, 4, 5], ..large });
+        assert_ne!(large, LargeEq { c: [7, 8, 0], ..large });
+
+        assert_eq!(GenericEqZ([1u8, 2, 3]), GenericEqZ([1u8, 2, 3]));
+        assert_ne!(GenericEqZ([1u8, 2, 3]), GenericEqZ([1u8, 2, 4]));
+        assert_eq!(GenericEqZ(0x1234_5678u32), GenericEqZ(0x1234_5678u32));
+        assert_ne!(GenericEqZ(0x1234_5678u32), GenericEqZ(0x1234_5679u32));
+    }
+
+    crate::derive_ord_fast_bytemuck!(LargeEq);
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, zerocopy::Immutable, zerocopy::IntoBytes)]
+    struct BigEndianKey {
+        a: zerocopy::byteorder::U16<zerocopy::byteorder::BigEndian>,
+        b: [u8; 3],
+        c: zerocopy::byteorder::U64<zerocopy::byteorder::BigEndian>,
+        d: zerocopy::byteorder::U64<zerocopy::byteorder::BigEndian>,
+        e: u8,
+    }
+
+    crate::derive_ord_fast_zerocopy!(lexicographic, BigEndianKey);
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, zerocopy::Immutable, zerocopy::IntoBytes)]
+    struct SignedKey {
+        signed: zerocopy::byteorder::I32<zerocopy::byteorder::BigEndian>,
+        flipped: zerocopy::byteorder::U32<zerocopy::byteorder::BigEndian>,
+    }
+
+    crate::derive_ord_fast_zerocopy!(lexicographic, SignedKey);
+
+    #[test]
+    fn test_ord() {
+        use std::cmp::Ordering;
+        let large = LargeEq { a: [1, 2, 3, 4, 5], b: 6, c: [7, 8, 9] };
+        assert_eq!(large.cmp(&large), Ordering::Equal);
+        let smaller = LargeEq { c: [7, 8, 0], ..large };
+        assert_eq!(large.cmp(&smaller), smaller.cmp(&large).reverse());
+        assert_ne!(large.cmp(&smaller), Ordering::Equal);
+
+        let key = |a: u16, b: [u8; 3], c: u64, d: u64, e: u8| BigEndianKey {
+            a: a.into(),
+            b,
+            c: c.into(),
+            d: d.into(),
+            e,
+        };
+        let values = [(1, [0, 0, 0], 0, 0, 0), (1, [0, 0, 1], 0, 0, 0), (0x100, [0; 3], 0, 0, 0),
+            (1, [0; 3], 1 << 56, 0, 0), (1, [0; 3], 0x100, 0, 0), (1, [0; 3], 0, 5, 0), (1, [0; 3], 0, 0, 9)];
+        for x in values {
+            for y in values {
+                assert_eq!(key(x.0, x.1, x.2, x.3, x.4).cmp(&key(y.0, y.1, y.2, y.3, y.4)), x.cmp(&y));
+            }
+        }
+
+        // a negative big-endian integer compares greater than a non-negative one,
+        // unless its sign bit is flipped
+        let signed = |x: i32| SignedKey { signed: x.into(), flipped: 0.into() };
+        let flipped = |x: i32| SignedKey { signed: 0.into(), flipped: (x as u32 ^ 0x8000_0000).into() };
+        assert!(signed(-1) > signed(0));
+        assert!(signed(-2) < signed(-1));
+        let values = [i32::MIN, -300, -1, 0, 1, 300, i32::MAX];
+        for x in values {
+            for y in values {
+                assert_eq!(flipped(x).cmp(&flipped(y)), x.cmp(&y));
+            }
+        }
+    }
+
+    #[repr(u8)]
+    #[derive(Clone, Copy, bytemuck::NoUninit)]
+    enum EnumU8B {
+        A,
+        B = 200,
+    }
+
+    derive_hash_fast_bytemuck!(EnumU8B);
+
+    #[repr(u16)]
+    #[derive(zerocopy::Immutable, zerocopy::IntoBytes)]
+    enum EnumU16Z {
+        A = 1,
+        B = 1000,
+    }
+
+    derive_hash_fast_zerocopy!(EnumU16Z);
+
+    #[repr(u32)]
+    #[derive(Clone, Copy, bytemuck::NoUninit)]
+    enum EnumU32B {
+        A,
+        B = 100_000,
+    }
+
+    derive_hash_fast_bytemuck!(EnumU32B);
+
+    #[repr(C)]
+    #[derive(Clone, Copy, bytemuck::NoUninit)]
+    struct WithEnums {
+        a: EnumU8B,
+        b: EnumU8B,
+        c: u16,
+        d: EnumU32B,
+    }
+
+    derive_hash_fast_bytemuck!(WithEnums);
+
+    #[derive(Clone, Copy)]
+    enum NoRepr {
+        A,
+        B,
+    }
+
+    derive_hash_fast_enum!(NoRepr as u8);
+
+    struct PaddedWithEnum {
+        a: NoRepr,
+        b: u16,
+    }
+
+    derive_hash_fast_fields!(PaddedWithEnum { a, b });
+
+    #[test]
+    fn test_enums() {
+        use std::hash::Hash;
+        let mut recorder = RecordingHasher::new();
+        EnumU8B::A.hash(&mut recorder);
+        EnumU16Z::A.hash(&mut recorder);
+        EnumU32B::A.hash(&mut recorder);
+        WithEnums { a: EnumU8B::A, b: EnumU8B::B, c: 5, d: EnumU32B::B }.hash(&mut recorder);
+        NoRepr::A.hash(&mut recorder);
+        PaddedWithEnum { a: NoRepr::B, b: 7 }.hash(&mut recorder);
+        assert_eq!(recorder.widths(), [U8, U16, U32, U64, U8, U32]);
+
+        let hash = |val: &dyn Fn(&mut std::hash::DefaultHasher)| {
+            use std::hash::Hasher;
+            let mut hasher = std::hash::DefaultHasher::new();
+            val(&mut hasher);
+            hasher.finish()
+        };
+        assert_ne!(hash(&|h| EnumU8B::A.hash(h)), hash(&|h| EnumU8B::B.hash(h)));
+        assert_ne!(hash(&|h| EnumU16Z::A.hash(h)), hash(&|h| EnumU16Z::B.hash(h)));
+        assert_ne!(hash(&|h| NoRepr::A.hash(h)), hash(&|h| NoRepr::B.hash(h)));
+        assert_eq!(hash(&|h| NoRepr::B.hash(h)), hash(&|h| 1u8.hash(h)));
+    }
+
+    #[repr(C, u8)]
+    enum WithPayload {
+        Small(u8),
+        Medium { a: u32, b: u16 },
+        Large([u64; 3], u8),
+        Empty,
+    }
+
+    derive_hash_fast_enum!(WithPayload { Small(x), Medium { a, b }, Large(big, small), Empty });
+
+    #[test]
+    fn test_enum_with_fields() {
+        use std::hash::Hash;
+        let mut recorder = RecordingHasher::new();
+        WithPayload::Small(1).hash(&mut recorder);
+        WithPayload::Medium { a: 1, b: 2 }.hash(&mut recorder);
+        WithPayload::Large([1, 2, 3], 4).hash(&mut recorder);
+        WithPayload::Empty.hash(&mut recorder);
+        // tag + 1 byte, tag + 6 bytes, tag + 25 bytes, tag only
+        assert_eq!(recorder.widths(), [U16, U64, U128, U128, U8]);
+
+        let hash = |val: &WithPayload| {
+            use std::hash::{DefaultHasher, Hasher};
+            let mut hasher = DefaultHasher::new();
+            val.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&WithPayload::Large([1, 2, 3], 4)), hash(&WithPayload::Large([1, 2, 3], 4)));
+        assert_ne!(hash(&WithPayload::Large([1, 2, 3], 4)), hash(&WithPayload::Large([1, 2, 3], 5)));
+        assert_ne!(hash(&WithPayload::Small(0)), hash(&WithPayload::Empty));
+    }
+
+    struct WithFloats {
+        a: f32,
+        b: [f64; 2],
+        c: (f32, u8),
+    }
+
+    derive_hash_fast_fields!(canonical_floats, WithFloats { a, b, c });
+
+    #[derive(Clone, Copy)]
+    struct WithFloatsRaw {
+        a: f32,
+    }
+
+    derive_hash_fast_fields!(WithFloatsRaw { a });
+
+    #[test]
+    fn test_canonical_floats() {
+        use std::hash::{DefaultHasher, Hash, Hasher};
+        fn hash(val: &impl Hash) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            val.hash(&mut hasher);
+            hasher.finish()
+        }
+        let negative_nan = f32::from_bits(f32::NAN.to_bits() | (1 << 31) | 1);
+        assert_eq!(
+            hash(&WithFloats { a: 0.0, b: [f64::NAN, 1.5], c: (f32::NAN, 1) }),
+            hash(&WithFloats { a: -0.0, b: [-f64::NAN, 1.5], c: (negative_nan, 1) }),
+        );
+        assert_ne!(
+            hash(&WithFloats { a: 0.0, b: [0.0, 1.5], c: (0.0, 1) }),
+            hash(&WithFloats { a: 0.0, b: [0.0, 2.5], c: (0.0, 1) }),
+        );
+        // without the opt-in, the raw bytes are hashed
+        assert_ne!(hash(&WithFloatsRaw { a: 0.0 }), hash(&WithFloatsRaw { a: -0.0 }));
+
+        assert_eq!(hash(&OrderedF32::new(0.0)), hash(&OrderedF32::new(-0.0)));
+        assert_eq!(hash(&OrderedF64::new(f64::NAN)), hash(&OrderedF64::new(-f64::NAN)));
+        assert_eq!(OrderedF64::new(-0.0).get().to_bits(), 0);
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[repr(C)]
+    #[derive(Clone, Copy, PartialEq, Eq, bytemuck::NoUninit)]
+    struct OrderedB {
+        a: OrderedF32,
+        b: OrderedF32,
+    }
+
+    #[cfg(feature = "bytemuck")]
+    derive_hash_fast_bytemuck!(OrderedB);
+
+    #[cfg(feature = "zerocopy")]
+    #[derive(PartialEq, Eq, zerocopy::Immutable, zerocopy::IntoBytes)]
+    struct OrderedZ {
+        a: OrderedF64,
+        b: u64,
+    }
+
+    #[cfg(feature = "zerocopy")]
+    derive_hash_fast_zerocopy!(OrderedZ);
+
+    #[test]
+    #[cfg(feature = "bytemuck")]
+    fn test_ordered_floats_in_bytemuck() {
+        use std::hash::{DefaultHasher, Hash, Hasher};
+        fn hash(val: &impl Hash) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            val.hash(&mut hasher);
+            hasher.finish()
+        }
+        let b = |a: f32, b: f32| OrderedB { a: a.into(), b: b.into() };
+        assert_eq!(hash(&b(0.0, f32::NAN)), hash(&b(-0.0, -f32::NAN)));
+    }
+
+    #[test]
+    #[cfg(feature = "zerocopy")]
+    fn test_ordered_floats_in_zerocopy() {
+        use std::hash::{DefaultHasher, Hash, Hasher};
+        fn hash(val: &impl Hash) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            val.hash(&mut hasher);
+            hasher.finish()
+        }
+        let z = |a: f64| OrderedZ { a: a.into(), b: 7 };
+        assert_eq!(hash(&z(0.0)), hash(&z(-0.0)));
+        assert_ne!(hash(&z(1.0)), hash(&z(-1.0)));
+    }
+
+    /// Hashed through a custom [`crate::AsHashBytes`] backend.
+    #[repr(transparent)]
+    struct Rgb([u8; 3]);
+
+    // SAFETY: `Rgb` is `repr(transparent)` over `[u8; 3]`, which has no padding,
+    // so a slice of `Rgb`s is the bytes of every element concatenated together
+    unsafe impl crate::AsHashBytes for Rgb {
+        const SIZE: usize = 3;
+
+        fn hash_bytes(&self) -> &[u8] {
+            &self.0
+        }
+
+        fn slice_bytes(s: &[Self]) -> &[u8] {
+            unsafe { core::slice::from_raw_parts(s.as_ptr().cast(), s.len() * Self::SIZE) }
+        }
+    }
+
+    crate::derive_hash_fast!(Rgb);
+
+    #[repr(transparent)]
+    #[derive(Clone, Copy, bytemuck::NoUninit)]
+    struct U16B(u16);
+
+    crate::derive_hash_fast!(bytemuck, U16B);
+
+    crate::derive_hash_fast!(zerocopy, impl<T: zerocopy::IntoBytes + zerocopy::Immutable> for GenericEqZ<T>);
+
+    #[test]
+    fn test_as_hash_bytes() {
+        use std::hash::Hash;
+        let mut recorder = RecordingHasher::new();
+        Rgb([1, 2, 3]).hash(&mut recorder);
+        U16B(1).hash(&mut recorder);
+        GenericEqZ([1u64; 3]).hash(&mut recorder);
+        assert_eq!(recorder.widths(), [U32, U16, U128, U64]);
+
+        // the slice view concatenates the elements, 6 bytes are padded to a `u64`
+        let recorder = RecordingHasher::record_slice(&[Rgb([1, 2, 3]), Rgb([4, 5, 6])]);
+        assert_eq!(recorder.writes(), [RecordedWrite::U64(u64::from_ne_bytes([1, 2, 3, 4, 5, 6, 0, 0]))]);
+
+        let hash = |val: &Rgb| {
+            use std::hash::{DefaultHasher, Hasher};
+            let mut hasher = DefaultHasher::new();
+            val.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&Rgb([1, 2, 3])), hash(&Rgb([1, 2, 3])));
+        assert_ne!(hash(&Rgb([1, 2, 3])), hash(&Rgb([1, 2, 4])));
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct FromFfi {
+        a: u32,
+        b: [u8; 4],
+        c: u16,
+        d: [u8; 2],
+    }
+
+    crate::unsafe_derive_hash_fast!(FromFfi; a, b, c, d);
+
+    #[repr(C)]
+    struct FromFfiTuple(u8, [u8; 7], u64);
+
+    crate::unsafe_derive_hash_fast!(FromFfiTuple; 0, 1, 2);
+
+    #[test]
+    fn test_unsafe_derive_hash_fast() {
+        use std::hash::{DefaultHasher, Hash, Hasher};
+        let mut recorder = RecordingHasher::new();
+        FromFfi { a: 1, b: [2; 4], c: 3, d: [4; 2] }.hash(&mut recorder);
+        FromFfiTuple(1, [2; 7], 3).hash(&mut recorder);
+        assert_eq!(recorder.widths(), [U128, U128]);
+
+        let hash = |val: &[FromFfi]| {
+            let mut hasher = DefaultHasher::new();
+            val.hash(&mut hasher);
+            hasher.finish()
+        };
+        let val = FromFfi { a: 1, b: [2; 4], c: 3, d: [4; 2] };
+        assert_eq!(hash(&[val, val]), hash(&[val, val]));
+        assert_ne!(hash(&[val, val]), hash(&[val, FromFfi { d: [4, 5], ..val }]));
+    }
+
+    struct PortableKey {
+        a: u8,
+        b: u16,
+        c: u32,
+        d: [u64; 2],
+    }
+
+    derive_hash_fast_fields!(portable, PortableKey { a, b, c, d });
+
+    #[derive(zerocopy::Immutable, zerocopy::IntoBytes)]
+    #[repr(C)]
+    struct PortableZ {
+        a: zerocopy::byteorder::U16<zerocopy::byteorder::LittleEndian>,
+        b: u8,
+    }
+
+    crate::derive_hash_fast!(portable, zerocopy, PortableZ);
+
+    #[test]
+    fn test_portable() {
+        use crate::byte_order::{BigEndian, LittleEndian};
+        use std::hash::Hash;
+
+        // the integers passed to the hasher are spelled out, so this passes on every target
+        let mut recorder = RecordingHasher::new();
+        PortableKey { a: 0x01, b: 0x0302, c: 0x0706_0504, d: [0x0f0e_0d0c_0b0a_0908, 0x1817_1615_1413_1211] }.hash(&mut recorder);
+        PortableZ { a: 0x0201.into(), b: 0x03 }.hash(&mut recorder);
+        assert_eq!(
+            recorder.writes(),
+            [
+                RecordedWrite::U128(0x110f_0e0d_0c0b_0a09_0807_0605_0403_0201),
+                RecordedWrite::U64(0x0018_1716_1514_1312),
+                RecordedWrite::U32(0x0003_0201),
+            ]
+        );
+
+        // Simulate hashing `a: u8, b: u16` on a little- and a big-endian target.
+        // In native mode each target stores the fields and loads the integers in its own byte order.
+        let mut little = RecordingHasher::new();
+        crate::write_bytes_of_constant_size::<LittleEndian>(&[0x01, 0x02, 0x03], &mut little);
+        let mut big = RecordingHasher::new();
+        crate::write_bytes_of_constant_size::<BigEndian>(&[0x01, 0x03, 0x02], &mut big);
+        assert_ne!(little.writes(), big.writes());
+        // In portable mode both targets store the fields in little-endian order,
+        // and only ever load them as little-endian integers.
+        let mut portable = RecordingHasher::new();
+        crate::hash_fixed_bytes_portable(&[0x01, 0x02, 0x03], &mut portable);
+        assert_eq!(portable.writes(), little.writes());
+        assert_eq!(portable.writes(), [RecordedWrite::U32(0x0003_0201)]);
+    }
+
+    #[test]
+    fn test_portable_types_on_big_endian() {
+        use crate::byte_order::{BigEndian, LittleEndian};
+        use crate::AsHashBytes;
+
+        /// Records the writes native mode and portable mode make for the bytes a big-endian target hashes.
+        fn big_endian_target(native: &[u8], portable: &[u8]) -> (RecordingHasher, RecordingHasher) {
+            let mut native_writes = RecordingHasher::new();
+            crate::write_bytes_of_constant_size::<BigEndian>(native, &mut native_writes);
+            let mut portable_writes = RecordingHasher::new();
+            crate::write_bytes_of_constant_size::<LittleEndian>(portable, &mut portable_writes);
+            (native_writes, portable_writes)
+        }
+
+        // the fields of `PortableZ` have a fixed byte order, so a big-endian target stores the same bytes
+        let value = PortableZ { a: 0x0201.into(), b: 0x03 };
+        let (native, portable) = big_endian_target(value.hash_bytes(), value.hash_bytes());
+        assert_ne!(native.writes(), portable.writes());
+        assert_eq!(RecordingHasher::record(&value).writes(), portable.writes());
+
+        // a big-endian target stores the fields of `PortableKey` in big-endian order,
+        // and the portable mode packs them with `to_le_bytes`, which reverses them
+        let key = PortableKey { a: 0x01, b: 0x0302, c: 0x0706_0504, d: [0x0f0e_0d0c_0b0a_0908, 0x1817_1615_1413_1211] };
+        let fields: [&[u8]; 5] = [
+            &key.a.to_be_bytes(),
+            &key.b.to_be_bytes(),
+            &key.c.to_be_bytes(),
+            &key.d[0].to_be_bytes(),
+            &key.d[1].to_be_bytes(),
+        ];
+        let native_bytes: Vec<u8> = fields.concat();
+        let portable_bytes: Vec<u8> = fields.iter().flat_map(|field| field.iter().rev()).copied().collect();
+        let (native, portable) = big_endian_target(&native_bytes, &portable_bytes);
+        assert_ne!(native.writes(), portable.writes());
+        assert_eq!(RecordingHasher::record(&key).writes(), portable.writes());
+    }
+
+    struct PointerSizedKey {
+        len: usize,
+        delta: isize,
+        id: u32,
+    }
+
+    derive_hash_fast_fields!(portable, PointerSizedKey { len, delta, id });
+
+    #[derive(Clone, Copy)]
+    #[repr(usize)]
+    enum Slot {
+        A = 1,
+    }
+
+    derive_hash_fast_enum!(Slot as usize);
+
+    struct SlotKey {
+        slot: Slot,
+        id: u32,
+    }
+
+    derive_hash_fast_fields!(portable, SlotKey { slot, id });
+
+    #[test]
+    fn test_portable_pointer_sized() {
+        use std::hash::Hash;
+
+        // 20 bytes on every target, rather than 12 on 32-bit ones
+        let mut recorder = RecordingHasher::new();
+        PointerSizedKey { len: 0x0201, delta: -1, id: 0x0403_0201 }.hash(&mut recorder);
+        assert_eq!(
+            recorder.writes(),
+            [RecordedWrite::U128(0xffff_ffff_ffff_ffff_0000_0000_0000_0201), RecordedWrite::U64(0x0000_0000_0403_0201)]
+        );
+
+        let mut recorder = RecordingHasher::new();
+        SlotKey { slot: Slot::A, id: 0x0403_0201 }.hash(&mut recorder);
+        assert_eq!(recorder.writes(), [RecordedWrite::U128(0x0000_0000_0403_0201_0000_0000_0000_0001)]);
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy, bytemuck::NoUninit)]
+    struct Planned {
+        a: u64,
+        b: u32,
+        c: [u16; 2],
+    }
+
+    derive_hash_fast_bytemuck!(plan = crate::U64Only, Planned);
+
+    struct PlannedFields {
+        a: [u8; 20],
+    }
+
+    derive_hash_fast_fields!(plan = crate::U128Preferred, portable, PlannedFields { a });
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, bytemuck::NoUninit)]
+    struct PlannedEqB {
+        a: u64,
+        b: u32,
+        c: u32,
+    }
+
+    crate::derive_hash_eq_fast_bytemuck!(plan = crate::U64Only, PlannedEqB);
+
+    #[repr(transparent)]
+    #[derive(Debug, zerocopy::Immutable, zerocopy::IntoBytes)]
+    struct PlannedEqZ<T>(T);
+
+    crate::derive_hash_eq_fast_zerocopy!(
+        plan = crate::SingleWrite,
+        impl<T: zerocopy::IntoBytes + zerocopy::Immutable> for PlannedEqZ<T>
+    );
+
+    #[test]
+    fn test_write_plans() {
+        use crate::{Optimal, SingleWrite, U128Preferred, U64Only, WritePlan};
+        use std::hash::{DefaultHasher, Hash, Hasher};
+
+        fn widths<P: WritePlan, const N: usize>() -> Vec<WriteWidth> {
+            let mut recorder = RecordingHasher::new();
+            P::hash_fixed_bytes(&[0u8; N], &mut recorder);
+            recorder.widths()
+        }
+
+        assert_eq!(widths::<Optimal, 3>(), [U32]);
+        assert_eq!(widths::<Optimal, 20>(), [U128, U64]);
+        assert_eq!(widths::<U64Only, 3>(), [U64]);
+        assert_eq!(widths::<U64Only, 12>(), [U64, U64]);
+        assert_eq!(widths::<U64Only, 20>(), [U64, U64, U64]);
+        assert_eq!(widths::<U128Preferred, 3>(), [U32]);
+        assert_eq!(widths::<U128Preferred, 8>(), [U64]);
+        assert_eq!(widths::<U128Preferred, 12>(), [U128]);
+        assert_eq!(widths::<U128Preferred, 20>(), [U128, U128]);
+        assert_eq!(widths::<U64Only, 0>(), []);
+        assert_eq!(widths::<U128Preferred, 0>(), []);
+
+        let mut recorder = RecordingHasher::new();
+        Planned { a: 1, b: 2, c: [3, 4] }.hash(&mut recorder);
+        assert_eq!(recorder.widths(), [U64, U64]);
+        let mut recorder = RecordingHasher::new();
+        PlannedFields { a: [1; 20] }.hash(&mut recorder);
+        assert_eq!(recorder.widths(), [U128, U128]);
+        assert_eq!(recorder.writes()[1], RecordedWrite::U128(0x0101_0101));
+
+        let mut recorder = RecordingHasher::new();
+        PlannedEqB { a: 1, b: 2, c: 3 }.hash(&mut recorder);
+        assert_eq!(recorder.widths(), [U64, U64]);
+        assert_eq!(PlannedEqB { a: 1, b: 2, c: 3 }, PlannedEqB { a: 1, b: 2, c: 3 });
+        assert_ne!(PlannedEqB { a: 1, b: 2, c: 3 }, PlannedEqB { a: 1, b: 2, c: 4 });
+        assert_eq!(PlannedEqZ(0x0102_0304u32), PlannedEqZ(0x0102_0304u32));
+        assert_ne!(PlannedEqZ(0x0102_0304u32), PlannedEqZ(0x0102_0305u32));
+
+        let bytes = [1u8, 2, 3, 4, 5];
+        let mut single = DefaultHasher::new();
+        SingleWrite::hash_fixed_bytes(&bytes, &mut single);
+        let mut expected = DefaultHasher::new();
+        expected.write(&bytes);
+        assert_eq!(single.finish(), expected.finish());
+
+        let mut planned = DefaultHasher::new();
+        PlannedEqZ([1u8, 2, 3, 4, 5]).hash(&mut planned);
+        assert_eq!(planned.finish(), expected.finish());
+    }
+
+    #[test]
+    fn test_overlapping_tail() {
+        use crate::{Optimal, OverlappingTail, WritePlan};
+
+        fn record<P: WritePlan, const N: usize>() -> RecordingHasher {
+            let mut bytes = [0u8; N];
+            for (i, byte) in bytes.iter_mut().enumerate() {
+                *byte = i as u8 + 1;
+            }
+            let mut recorder = RecordingHasher::new();
+            P::hash_fixed_bytes_portable(&bytes, &mut recorder);
+            recorder
+        }
+
+        macro_rules! assert_same_widths {
+            ($($n:literal)*) => {
+                $(assert_eq!(record::<OverlappingTail, $n>().widths(), record::<Optimal, $n>().widths());)*
+            };
+        }
+        assert_same_widths!(0 1 2 3 4 5 6 7 8 9 10 15 16 17 20 24 25 31 32 33 40);
+
+        assert_eq!(record::<OverlappingTail, 3>().writes(), [RecordedWrite::U32(0x0302_0201)]);
+        assert_eq!(record::<OverlappingTail, 6>().writes(), [RecordedWrite::U64(0x0605_0403_0403_0201)]);
+        assert_eq!(
+            record::<OverlappingTail, 10>().writes(),
+            [RecordedWrite::U128(0x0a09_0807_0605_0403_0807_0605_0403_0201)]
+        );
+        assert_eq!(
+            record::<OverlappingTail, 20>().writes(),
+            [RecordedWrite::U128(0x100f_0e0d_0c0b_0a09_0807_0605_0403_0201), RecordedWrite::U64(0x1413_1211_100f_0e0d)]
+        );
+        assert_eq!(
+            record::<OverlappingTail, 30>().writes(),
+            [
+                RecordedWrite::U128(0x100f_0e0d_0c0b_0a09_0807_0605_0403_0201),
+                RecordedWrite::U128(0x1e1d_1c1b_1a19_1817_1615_1413_1211_100f),
+            ]
+        );
+        assert_eq!(record::<OverlappingTail, 16>().writes(), record::<Optimal, 16>().writes());
+    }
+
+    /// The expansions must not resolve through items of the user's crate with the same names.
+    #[allow(dead_code)]
+    mod hygiene {
+        mod core {}
+
+        #[derive(Clone, Copy, ::bytemuck::NoUninit)]
+        #[repr(C)]
+        struct B(u32);
+
+        crate::derive_hash_eq_fast_bytemuck!(B);
+        crate::derive_ord_fast_bytemuck!(B);
+
+        #[derive(::zerocopy::Immutable, ::zerocopy::IntoBytes)]
+        struct Z(u32);
+
+        crate::derive_hash_eq_fast_zerocopy!(Z);
+        crate::derive_ord_fast_zerocopy!(Z);
+
+        struct Fields(u8, u16);
+
+        crate::derive_hash_fast_fields!(Fields { 0, 1 });
+
+        #[derive(Clone, Copy)]
+        enum E {
+            A(u8),
+            B,
+        }
+
+        crate::derive_hash_fast_enum!(E { A(a), B });
+    }
+
+    #[test]
+    fn test_hash_fixed_bytes() {
+        let mut recorder = RecordingHasher::new();
+        crate::hash_fixed_bytes(&[1u8; 3], &mut recorder);
+        crate::hash_fixed_bytes(&[1u8; 20], &mut recorder);
+        crate::hash_fixed_bytes(&[1u8; 0], &mut recorder);
+        assert_eq!(recorder.widths(), [U32, U128, U64]);
+    }
+
+    #[test]
+    fn test_fast_array() {
+        use crate::{FastArray, OrderedF32};
+        use std::hash::Hash;
+
+        let mut recorder = RecordingHasher::new();
+        FastArray([7u8; 32]).hash(&mut recorder);
+        FastArray([1u32, 2, 3]).hash(&mut recorder);
+        FastArray([OrderedF32::new(1.0); 2]).hash(&mut recorder);
+        FastArray::<u16, 0>([]).hash(&mut recorder);
+        assert_eq!(recorder.widths(), [U128, U128, U128, U64]);
+
+        // a slice of two `[u16; 2]` is a single `write_u64` after the length prefix
+        let mut recorder = RecordingHasher::new();
+        Hash::hash_slice(FastArray::from_slice(&[[1u16, 2], [3, 4]]), &mut recorder);
+        assert_eq!(recorder.widths(), [U64]);
+
+        let mut map = std::collections::HashMap::new();
+        map.insert(FastArray::from([1u32, 2, 3]), "a");
+        assert_eq!(map.get(FastArray::from_ref(&[1, 2, 3])), Some(&"a"));
+        assert_eq!(map.get(FastArray::from_ref(&[1, 2, 4])), None);
+    }
+
+    #[test]
+    #[cfg(all(feature = "bytemuck", feature = "zerocopy"))]
+    fn test_hash_as_bytes_wrapper() {
+        use crate::{AsHashBytes, Bytemuck, HashAsBytes, Zerocopy};
+        use std::hash::{DefaultHasher, Hash, Hasher};
+
+        fn hash(value: &impl Hash) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        // `FooZ` and `FooB` stand in for foreign types, only their traits are used
+        let z = HashAsBytes::<_, Zerocopy>::new(FooZ { a: 5, b: 10, c: 'a' });
+        let b = HashAsBytes::<_, Bytemuck>::new(FooB { a: 5, b: 10, c: 'a' });
+        assert_eq!(hash(&z), hash_struct_zerocopy(FooZ { a: 5, b: 10, c: 'a' }));
+        assert_eq!(hash(&b), hash_struct_bytemuck(*b));
+
+        assert!(z == HashAsBytes::new(FooZ { a: 5, b: 10, c: 'a' }));
+        assert!(z != HashAsBytes::new(FooZ { a: 5, b: 11, c: 'a' }));
+        assert_eq!(z.b, 10);
+
+        // -0.0 and 0.0 have different bytes
+        let zero = HashAsBytes::<f32, Bytemuck>::new(0.0);
+        assert!(zero != HashAsBytes::new(-0.0));
+        assert!(HashAsBytes::<f32, Bytemuck>::new(f32::NAN) == HashAsBytes::new(f32::NAN));
+
+        let mut recorder = RecordingHasher::new();
+        Hash::hash_slice(HashAsBytes::<u32, Zerocopy>::from_slice(&[1, 2]), &mut recorder);
+        assert_eq!(recorder.widths(), [U64]);
+
+        // the bytes come from the wrappers' `AsHashBytes` implementations
+        assert_eq!(z.hash_bytes(), zerocopy::IntoBytes::as_bytes(&*z));
+        assert_eq!(HashAsBytes::<u16, Bytemuck>::slice_bytes(HashAsBytes::from_slice(&[1, 2])), bytemuck::cast_slice::<u16, u8>(&[1, 2]));
+
+        let mut map = std::collections::HashMap::new();
+        map.insert(b, "b");
+        assert_eq!(map.get(HashAsBytes::from_ref(&FooB { a: 5, b: 10, c: 'a' })), Some(&"b"));
+    }
+
+    #[test]
+    fn test_short_slice() {
+        use std::hash::{DefaultHasher, Hash, Hasher};
+
+        let mut recorder = RecordingHasher::new();
+        Hash::hash_slice(&[FooB { a: 1, b: 2, c: 'a' }; 2], &mut recorder);
+        Hash::hash_slice(&[PortableZ { a: 0x0201.into(), b: 0x03 }, PortableZ { a: 0x0201.into(), b: 0x03 }], &mut recorder);
+        assert_eq!(recorder.widths(), [U128, U64]);
+        assert_eq!(recorder.writes()[1], RecordedWrite::U64(0x0302_0103_0201));
+
+        // longer slices are a single variable-length write
+        let long = [FooB { a: 1, b: 2, c: 'a' }; 5];
+        let mut hasher = DefaultHasher::new();
+        Hash::hash_slice(&long, &mut hasher);
+        let mut expected = DefaultHasher::new();
+        expected.write(bytemuck::cast_slice(&long));
+        assert_eq!(hasher.finish(), expected.finish());
+
+        // every short length is the zero-padded integer, in both byte orders
+        let bytes: [u8; 16] = core::array::from_fn(|i| i as u8 + 1);
+        for len in 1..=16 {
+            let mut padded = [0u8; 16];
+            padded[..len].copy_from_slice(&bytes[..len]);
+            let (native, portable) = match len {
+                1 => (RecordedWrite::U8(padded[0]), RecordedWrite::U8(padded[0])),
+                2 => (
+                    RecordedWrite::U16(u16::from_ne_bytes(padded[..2].try_into().unwrap())),
+                    RecordedWrite::U16(u16::from_le_bytes(padded[..2].try_into().unwrap())),
+                ),
+                3..=4 => (
+                    RecordedWrite::U32(u32::from_ne_bytes(padded[..4].try_into().unwrap())),
+                    RecordedWrite::U32(u32::from_le_bytes(padded[..4].try_into().unwrap())),
+                ),
+                5..=8 => (
+                    RecordedWrite::U64(u64::from_ne_bytes(padded[..8].try_into().unwrap())),
+                    RecordedWrite::U64(u64::from_le_bytes(padded[..8].try_into().unwrap())),
+                ),
+                _ => (RecordedWrite::U128(u128::from_ne_bytes(padded)), RecordedWrite::U128(u128::from_le_bytes(padded))),
+            };
+            let mut recorder = RecordingHasher::new();
+            crate::hash_slice_bytes(&bytes[..len], &mut recorder);
+            crate::hash_slice_bytes_portable(&bytes[..len], &mut recorder);
+            assert_eq!(recorder.writes(), [native, portable], "length {len}");
+        }
+    }
+
+    #[test]
+    fn test_coalescing_hasher() {
+        use crate::{CoalescingBuildHasher, CoalescingHasher};
+        use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher, Hash, Hasher};
+        use std::string::String;
+
+        #[derive(Hash)]
+        struct Mixed {
+            a: u8,
+            b: u16,
+            name: String,
+        }
+
+        // 1 + 2 + 5 bytes of the string + its 0xff terminator = 9 bytes, padded to a u128
+        let value = Mixed { a: 1, b: 2, name: "hello".into() };
+        let mut expected_bytes = std::vec![1u8];
+        expected_bytes.extend_from_slice(&2u16.to_le_bytes());
+        expected_bytes.extend_from_slice(b"hello\xff");
+        let mut expected = DefaultHasher::new();
+        expected.write_u128(crate::pad_to_u128::<crate::byte_order::LittleEndian>(&expected_bytes));
+        let build = CoalescingBuildHasher::<BuildHasherDefault<DefaultHasher>>::default();
+        assert_eq!(build.hash_one(&value), expected.finish());
+
+        // a full buffer is flushed in `write_u128` chunks as soon as it fills up
+        let mut hasher = CoalescingHasher::new(RecordingHasher::new());
+        for i in 0..33u16 {
+            hasher.write_u16(i);
+        }
+        assert_eq!(hasher.get_ref().widths(), [U128; 4]);
+
+        // writes that straddle two chunks are split between them
+        let mut hasher = CoalescingHasher::new(RecordingHasher::new());
+        hasher.write_u8(0xaa);
+        hasher.write_u128(0x0f0e_0d0c_0b0a_0908_0706_0504_0302_0100);
+        hasher.write_u128(0x1f1e_1d1c_1b1a_1918_1716_1514_1312_1110);
+        assert_eq!(
+            hasher.get_ref().writes(),
+            [
+                RecordedWrite::U128(0x0e0d_0c0b_0a09_0807_0605_0403_0201_00aa),
+                RecordedWrite::U128(0x1e1d_1c1b_1a19_1817_1615_1413_1211_100f),
+            ]
+        );
+
+        // `finish` doesn't consume the buffered bytes
+        let mut hasher = CoalescingHasher::new(DefaultHasher::new());
+        hasher.write_u32(7);
+        let first = hasher.finish();
+        assert_eq!(hasher.finish(), first);
+        hasher.write_u8(1);
+        assert_ne!(hasher.finish(), first);
+    }
+
+    #[test]
+    #[cfg(feature = "fixed-hasher")]
+    fn test_fixed_hasher() {
+        use crate::{FixedBuildHasher, FixedHasher};
+        use std::collections::HashSet;
+        use std::hash::{BuildHasher, Hasher};
+
+        // same seed, same hash; different seed or value, different hash
+        let build = FixedBuildHasher::new(1);
+        let value = FooB { a: 5, b: 10, c: 'a' };
+        assert_eq!(build.hash_one(value), build.hash_one(value));
+        assert_ne!(build.hash_one(value), FixedBuildHasher::new(2).hash_one(value));
+        assert_ne!(build.hash_one(value), build.hash_one(FooB { a: 6, b: 10, c: 'a' }));
+
+        // both halves of a `u128` are mixed in
+        let hash_u128 = |i: u128| {
+            let mut hasher = FixedHasher::default();
+            hasher.write_u128(i);
+            hasher.finish()
+        };
+        assert_ne!(hash_u128(1), hash_u128(1 << 64));
+        assert_ne!(hash_u128(0), hash_u128(1 << 127));
+
+        // a high half equal to a public constant must not zero out the multiplication,
+        // which would make the hash independent of the seed and the low half
+        const HIGH: u128 = 0x1319_8a2e_0370_7344 << 64;
+        let hash_seeded = |seed: u64, i: u128| {
+            let mut hasher = FixedHasher::with_seed(seed);
+            hasher.write_u128(i);
+            hasher.finish()
+        };
+        let hashes: HashSet<u64> = [0, 1, 0xdead_beef]
+            .into_iter()
+            .flat_map(|seed| [1u128, 2, 3].map(|lo| hash_seeded(seed, HIGH | lo)))
+            .collect();
+        assert_eq!(hashes.len(), 9);
+        assert!(!hashes.contains(&0));
+
+        // the halves are not interchangeable
+        let pairs = [(1u64, 2u64), (0x1234, 0xdead_beef), (0, u64::MAX)];
+        for (a, b) in pairs {
+            assert_ne!(hash_u128(u128::from(a) << 64 | u128::from(b)), hash_u128(u128::from(b) << 64 | u128::from(a)));
+        }
+
+        // a high half equal to the key doesn't discard the earlier writes
+        for seed in [0, 1, 0xdead_beef] {
+            let key = FixedHasher::with_seed(seed).key;
+            let hashes: HashSet<u64> = (1..=3u64)
+                .map(|first| {
+                    let mut hasher = FixedHasher::with_seed(seed);
+                    hasher.write_u64(first);
+                    hasher.write_u128(u128::from(key) << 64 | 5);
+                    hasher.finish()
+                })
+                .collect();
+            assert_eq!(hashes.len(), 3);
+        }
+
+        // trailing zeroes in a variable-length write are not lost to padding
+        let hash_bytes = |bytes: &[u8]| {
+            let mut hasher = FixedHasher::default();
+            hasher.write(bytes);
+            hasher.finish()
+        };
+        assert_ne!(hash_bytes(b"a"), hash_bytes(b"a\0"));
+
+        let mut set = HashSet::with_hasher(FixedBuildHasher::default());
+        for i in 0..1000u32 {
+            assert!(set.insert(i));
+        }
+        assert!((0..1000).all(|i| set.contains(&i)));
+    }
+
+    #[test]
+    fn test_recording_hasher() {
+        let value = FooB { a: 5, b: 10, c: 'a' };
+        let recorder = RecordingHasher::record(&value);
+        assert_eq!(recorder.writes(), [RecordedWrite::U64(u64::from_ne_bytes(bytemuck::cast(value)))]);
+        crate::assert_write_plan!(FooB = value, [U64]);
+        crate::assert_write_plan!(Large = Large { a: [1; 24], b: 2 }, [U128, U128, U128, U128, U128, U128, U64]);
+        crate::assert_write_plan!([FooB] = [value; 2], [U128]);
+        crate::assert_write_plan!([FooB] = [], []);
+        crate::assert_write_plan!(u8, [U8]);
+
+        // `#[derive(Hash)]` makes one call per field, and the length of a slice goes through `write_usize`
+        #[derive(Hash, Default)]
+        struct Derived {
+            a: u8,
+            b: i16,
+            c: [u8; 2],
+        }
+        assert_eq!(
+            RecordingHasher::record(&Derived::default()).widths(),
+            [WriteWidth::U8, WriteWidth::U16, WriteWidth::Usize, WriteWidth::Bytes]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "unexpected writes: expected [U128], got [U64(")]
+    fn test_assert_write_plan_failure() {
+        crate::assert_write_plan!(FooB = FooB { a: 5, b: 10, c: 'a' }, [U128]);
+    }
+
+    #[test]
+    fn test_write_plan_matches_writes() {
+        use crate::{optimal_write_layout, WriteLayout};
+        use std::string::ToString;
+
+        let bytes = [0u8; 100];
+        for size in 0..=bytes.len() {
+            let mut recorder = RecordingHasher::new();
+            crate::write_bytes_of_constant_size::<crate::byte_order::NativeEndian>(&bytes[..size], &mut recorder);
+            let layout = optimal_write_layout(size);
+            assert_eq!(layout.widths().collect::<Vec<_>>(), recorder.widths(), "size {size}");
+            let written: usize = layout
+                .widths()
+                .map(|width| match width {
+                    WriteWidth::U8 => 1,
+                    WriteWidth::U16 => 2,
+                    WriteWidth::U32 => 4,
+                    WriteWidth::U64 => 8,
+                    WriteWidth::U128 => 16,
+                    WriteWidth::Usize | WriteWidth::Bytes => unreachable!(),
+                })
+                .sum();
+            assert_eq!(written, size + layout.padding, "size {size}");
+        }
+        assert_eq!(WriteLayout::optimal_for_size_of::<Large>(), optimal_write_layout(100));
+        assert_eq!(optimal_write_layout(0).to_string(), "no writes");
+        assert_eq!(optimal_write_layout(3).to_string(), "write_u32 (1 byte of padding)");
+        assert_eq!(optimal_write_layout(32).to_string(), "2 × write_u128");
+    }
+
+    #[test]
+    fn test_hash_eq_consistency() {
+        use crate::check_hash_eq_consistency;
+        use std::hash::DefaultHasher;
+
+        #[repr(C)]
+        #[derive(Clone, Copy, Debug, arbitrary::Arbitrary, bytemuck::NoUninit)]
+        struct Consistent {
+            a: u32,
+            b: u16,
+            c: u8,
+            d: u8,
+        }
+        crate::derive_hash_eq_fast_bytemuck!(Consistent);
+        assert!(check_hash_eq_consistency::<Consistent, DefaultHasher>().is_ok());
+
+        // ignores `cache` in `eq`, but not in `hash`
+        #[repr(C)]
+        #[derive(Clone, Copy, Debug, arbitrary::Arbitrary, bytemuck::NoUninit)]
+        struct WithCache {
+            key: u64,
+            cache: u32,
+            kind: u32,
+        }
+        impl PartialEq for WithCache {
+            fn eq(&self, other: &Self) -> bool {
+                self.key == other.key && self.kind == other.kind
+            }
+        }
+        crate::derive_hash_fast_bytemuck!(WithCache);
+        let inconsistency = check_hash_eq_consistency::<WithCache, DefaultHasher>().unwrap_err();
+        assert!(!inconsistency.offsets.is_empty());
+        assert!(inconsistency.offsets.iter().all(|offset| (8..12).contains(offset)));
+
+        // no byte view, the offsets are into the packed fields
+        #[derive(Clone, Debug, arbitrary::Arbitrary)]
+        struct FieldsWithCache {
+            key: u8,
+            cache: u32,
+            kind: u16,
+        }
+        impl PartialEq for FieldsWithCache {
+            fn eq(&self, other: &Self) -> bool {
+                self.key == other.key && self.kind == other.kind
+            }
+        }
+        crate::derive_hash_fast_fields!(FieldsWithCache { key, cache, kind });
+        let inconsistency = check_hash_eq_consistency::<FieldsWithCache, DefaultHasher>().unwrap_err();
+        assert!(!inconsistency.offsets.is_empty());
+        assert!(inconsistency.offsets.iter().all(|offset| (1..5).contains(offset)));
+    }
 }
@@ -0,0 +1,229 @@
+use core::cmp::Ordering;
+
+/// Derives a fast `PartialOrd` and `Ord` implementation for `bytemuck` types.
+///
+/// The byte representations of two values obtained through `bytemuck::bytes_of()`
+/// are compared in the same `u32`, `u64` and `u128` chunks that
+/// [`derive_hash_fast_bytemuck!`](crate::derive_hash_fast_bytemuck) feeds to the hasher.
+///
+/// Two orderings are available:
+///
+/// - `derive_ord_fast_bytemuck!(MyType)` loads the chunks as native-endian integers.
+///   This is the fastest option, but the resulting order is arbitrary:
+///   it is total and consistent with byte equality, which is all that sorting,
+///   binary search and deduplication need, but it does not follow the values of the fields.
+/// - `derive_ord_fast_bytemuck!(lexicographic, MyType)` compares the bytes lexicographically,
+///   like `memcmp`. If every field is a `u8`, an array of `u8`, or an unsigned integer stored in
+///   big-endian byte order, this matches the order `#[derive(Ord)]` would produce.
+///   Signed integers don't: the sign bit of a negative value makes it compare greater than
+///   every non-negative one. Store them as unsigned with the sign bit flipped,
+///   e.g. `(x as u32 ^ 0x8000_0000).to_be()` for an `i32`, to get the order of `#[derive(Ord)]`.
+///
+/// The type must also implement `Eq`, preferably through
+/// [`derive_eq_fast_bytemuck!`](crate::derive_eq_fast_bytemuck) so that both agree by construction.
+///
+/// Generic types are supported with the same `impl<...> for Type` syntax
+/// as [`derive_hash_fast_bytemuck!`](crate::derive_hash_fast_bytemuck).
+///
+/// # Examples
+///
+/// ```
+/// use derive_hash_fast::{derive_eq_fast_bytemuck, derive_ord_fast_bytemuck};
+/// use bytemuck::NoUninit;
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, Debug, NoUninit)]
+/// struct MyStruct {
+///     a: u32,
+///     b: u16,
+///     c: u16,
+/// }
+///
+/// derive_eq_fast_bytemuck!(MyStruct);
+/// derive_ord_fast_bytemuck!(MyStruct);
+///
+/// let mut keys = vec![MyStruct{a: 1, b: 2, c: 3}, MyStruct{a: 4, b: 5, c: 6}, MyStruct{a: 1, b: 2, c: 3}];
+/// keys.sort();
+/// keys.dedup();
+/// assert_eq!(keys.len(), 2);
+/// ```
+#[macro_export]
+macro_rules! derive_ord_fast_bytemuck {
+    (@impl $cmp:ident [$($generics:tt)*] [$T:ty] [$($where:tt)*]) => {
+        impl<$($generics)*> ::core::cmp::PartialOrd for $T where $($where)* {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> ::core::option::Option<::core::cmp::Ordering> {
+                ::core::option::Option::Some(::core::cmp::Ord::cmp(self, other))
+            }
+        }
+
+        impl<$($generics)*> ::core::cmp::Ord for $T where $($where)* {
+            #[inline]
+            fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+                $crate::$cmp::<Self>($crate::__bytemuck!(bytes_of(self)), $crate::__bytemuck!(bytes_of(other)))
+            }
+        }
+    };
+    (@mode $cmp:ident impl < $($rest:tt)*) => {
+        $crate::__split_impl_generics!(derive_ord_fast_bytemuck {$cmp} [] $($rest)*);
+    };
+    (@mode $cmp:ident $T:ty) => {
+        $crate::derive_ord_fast_bytemuck!(@impl $cmp [] [$T] []);
+    };
+    (lexicographic, $($input:tt)*) => {
+        $crate::derive_ord_fast_bytemuck!(@mode bytes_cmp_lexicographic_for $($input)*);
+    };
+    ($($input:tt)*) => {
+        $crate::derive_ord_fast_bytemuck!(@mode bytes_cmp_for $($input)*);
+    };
+}
+
+/// Derives a fast `PartialOrd` and `Ord` implementation for `zerocopy` types.
+///
+/// The byte representations of two values obtained through `.as_bytes()`
+/// are compared in the same `u32`, `u64` and `u128` chunks that
+/// [`derive_hash_fast_zerocopy!`](crate::derive_hash_fast_zerocopy) feeds to the hasher.
+///
+/// Two orderings are available:
+///
+/// - `derive_ord_fast_zerocopy!(MyType)` loads the chunks as native-endian integers.
+///   This is the fastest option, but the resulting order is arbitrary:
+///   it is total and consistent with byte equality, which is all that sorting,
+///   binary search and deduplication need, but it does not follow the values of the fields.
+/// - `derive_ord_fast_zerocopy!(lexicographic, MyType)` compares the bytes lexicographically,
+///   like `memcmp`. If every field is a `u8`, an array of `u8`, or a big-endian unsigned integer
+///   from `zerocopy::byteorder`, this matches the order `#[derive(Ord)]` would produce.
+///   Signed integers such as `I32<BigEndian>` don't: the sign bit of a negative value makes it
+///   compare greater than every non-negative one. Store them as unsigned with the sign bit flipped,
+///   e.g. `U32::new(x as u32 ^ 0x8000_0000)` for an `i32`, to get the order of `#[derive(Ord)]`.
+///
+/// The type must also implement `Eq`, preferably through
+/// [`derive_eq_fast_zerocopy!`](crate::derive_eq_fast_zerocopy) so that both agree by construction.
+///
+/// Generic types are supported with the same `impl<...> for Type` syntax
+/// as [`derive_hash_fast_zerocopy!`](crate::derive_hash_fast_zerocopy).
+///
+/// # Examples
+///
+/// ```
+/// use derive_hash_fast::{derive_eq_fast_zerocopy, derive_ord_fast_zerocopy};
+/// use zerocopy::byteorder::{BigEndian, U16, U32};
+/// use zerocopy::{Immutable, IntoBytes};
+///
+/// #[derive(Debug, Immutable, IntoBytes)]
+/// struct MyKey {
+///     major: U32<BigEndian>,
+///     minor: U16<BigEndian>,
+///     patch: U16<BigEndian>,
+/// }
+///
+/// derive_eq_fast_zerocopy!(MyKey);
+/// derive_ord_fast_zerocopy!(lexicographic, MyKey);
+///
+/// let key = |major: u32, minor: u16, patch: u16| MyKey {
+///     major: major.into(),
+///     minor: minor.into(),
+///     patch: patch.into(),
+/// };
+/// // ordered by fields in declaration order
+/// assert!(key(1, 2, 300) < key(1, 3, 0));
+/// assert!(key(1, 300, 0) < key(2, 0, 0));
+/// ```
+#[macro_export]
+macro_rules! derive_ord_fast_zerocopy {
+    (@impl $cmp:ident [$($generics:tt)*] [$T:ty] [$($where:tt)*]) => {
+        impl<$($generics)*> ::core::cmp::PartialOrd for $T where $($where)* {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> ::core::option::Option<::core::cmp::Ordering> {
+                ::core::option::Option::Some(::core::cmp::Ord::cmp(self, other))
+            }
+        }
+
+        impl<$($generics)*> ::core::cmp::Ord for $T where $($where)* {
+            #[inline]
+            fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+                $crate::$cmp::<Self>(
+                    $crate::__zerocopy!(IntoBytes::as_bytes(self)),
+                    $crate::__zerocopy!(IntoBytes::as_bytes(other)),
+                )
+            }
+        }
+    };
+    (@mode $cmp:ident impl < $($rest:tt)*) => {
+        $crate::__split_impl_generics!(derive_ord_fast_zerocopy {$cmp} [] $($rest)*);
+    };
+    (@mode $cmp:ident $T:ty) => {
+        $crate::derive_ord_fast_zerocopy!(@impl $cmp [] [$T] []);
+    };
+    (lexicographic, $($input:tt)*) => {
+        $crate::derive_ord_fast_zerocopy!(@mode bytes_cmp_lexicographic_for $($input)*);
+    };
+    ($($input:tt)*) => {
+        $crate::derive_ord_fast_zerocopy!(@mode bytes_cmp_for $($input)*);
+    };
+}
+
+/// Compares two byte representations of `T` in an arbitrary but total order,
+/// loading them as native-endian integers.
+#[doc(hidden)]
+#[inline]
+pub fn bytes_cmp_for<T>(a: &[u8], b: &[u8]) -> Ordering {
+    bytes_cmp_native(core::mem::size_of::<T>(), a, b)
+}
+
+/// Compares two byte representations of `T` lexicographically,
+/// loading them as big-endian integers.
+#[doc(hidden)]
+#[inline]
+pub fn bytes_cmp_lexicographic_for<T>(a: &[u8], b: &[u8]) -> Ordering {
+    bytes_cmp_big_endian(core::mem::size_of::<T>(), a, b)
+}
+
+/// Copies `bytes` into a zero-padded array. Equal padding on both sides
+/// does not change the outcome of a comparison.
+#[inline(always)]
+fn padded<const W: usize>(bytes: &[u8]) -> [u8; W] {
+    let mut padded_bytes = [0u8; W];
+    padded_bytes[..bytes.len()].copy_from_slice(bytes);
+    padded_bytes
+}
+
+macro_rules! bytes_cmp_of_constant_size {
+    ($name:ident, $from_bytes:ident) => {
+        /// `size` must be a compile-time constant at every call site,
+        /// which is why this is `#[inline(always)]`.
+        #[inline(always)]
+        fn $name(size: usize, a: &[u8], b: &[u8]) -> Ordering {
+            const SIZEOF_U128: usize = core::mem::size_of::<u128>();
+            assert!(a.len() == size && b.len() == size);
+            // This match incurs no runtime overhead in release mode because it matches on a constant.
+            match size {
+                0 => Ordering::Equal,
+                1 => a[0].cmp(&b[0]),
+                2 => u16::$from_bytes(padded(a)).cmp(&u16::$from_bytes(padded(b))),
+                3..=4 => u32::$from_bytes(padded(a)).cmp(&u32::$from_bytes(padded(b))),
+                5..=8 => u64::$from_bytes(padded(a)).cmp(&u64::$from_bytes(padded(b))),
+                9..=16 => u128::$from_bytes(padded(a)).cmp(&u128::$from_bytes(padded(b))),
+                17.. => {
+                    let a_chunks = a.chunks_exact(SIZEOF_U128);
+                    let b_chunks = b.chunks_exact(SIZEOF_U128);
+                    let (a_remainder, b_remainder) = (a_chunks.remainder(), b_chunks.remainder());
+                    for (a, b) in a_chunks.zip(b_chunks) {
+                        match u128::$from_bytes(padded(a)).cmp(&u128::$from_bytes(padded(b))) {
+                            Ordering::Equal => (),
+                            unequal => return unequal,
+                        }
+                    }
+                    match a_remainder.len() {
+                        0 => Ordering::Equal,
+                        1..=8 => u64::$from_bytes(padded(a_remainder)).cmp(&u64::$from_bytes(padded(b_remainder))),
+                        _ => u128::$from_bytes(padded(a_remainder)).cmp(&u128::$from_bytes(padded(b_remainder))),
+                    }
+                }
+            }
+        }
+    };
+}
+
+bytes_cmp_of_constant_size!(bytes_cmp_native, from_ne_bytes);
+bytes_cmp_of_constant_size!(bytes_cmp_big_endian, from_be_bytes);
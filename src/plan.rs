@@ -0,0 +1,213 @@
+use core::hash::Hasher;
+
+use crate::byte_order::{ByteOrder, LittleEndian, NativeEndian};
+use crate::{pad_to_u128, pad_to_u64};
+
+/// How the bytes of a value are split into calls to the [`Hasher`].
+///
+/// Fewer, wider writes are faster with most hashers, but each hasher has its own
+/// fast and slow paths: some handle `write_u128` as two `write_u64` calls,
+/// others have a bulk path for `write` that beats any sequence of primitive writes.
+/// A plan is selected per type with the `plan = ...,` prefix of
+/// [`derive_hash_fast!`](crate::derive_hash_fast) and [`derive_hash_fast_fields!`](crate::derive_hash_fast_fields),
+/// and defaults to [`Optimal`]. Whichever plan is selected, the sequence of writes
+/// only depends on the size of the type and is chosen at compile time.
+///
+/// Which plan is fastest depends on the hasher, the size of the type and the hardware.
+/// To pick one, run the `Write plan` benchmarks in `benches/hash.rs`
+/// (`cargo bench --bench hash -- "Write plan"`), which compare every plan against `#[derive(Hash)]`
+/// for an 80-bit, a 160-bit and a 768-bit struct with each hasher, and add your own types
+/// and hasher to `bench_write_plans` if they differ from those.
+/// [`OverlappingTail`] makes the same writes as [`Optimal`] with different loads,
+/// see its documentation and the `Tail strategy` benchmarks.
+///
+/// On one core of a virtualized Intel Xeon with Rust 1.95, these plans were the fastest
+/// or within noise of it in two runs of those benchmarks:
+///
+/// - `std::hash::DefaultHasher`: [`SingleWrite`], 15–30% faster than [`Optimal`] for the 80-bit
+///   and 160-bit structs. For the 768-bit struct the two swapped places between runs.
+/// - `rustc_hash::FxHasher`: [`Optimal`], on par with `#[derive(Hash)]` for the 768-bit struct.
+///   [`SingleWrite`] is about 6 times slower for the smaller structs.
+/// - `rapidhash::RapidHasher`: [`Optimal`] for the 80-bit and 160-bit structs,
+///   [`SingleWrite`] for the 768-bit struct, where it is about twice as fast.
+/// - `ahash::AHasher`: [`U64Only`] for the 80-bit and 160-bit structs, up to 15% faster than [`Optimal`],
+///   and [`SingleWrite`] for the 768-bit struct, about 20% faster than [`Optimal`].
+/// - `xxhash_rust::xxh3::Xxh3Default`: [`Optimal`] for the 80-bit and 160-bit structs,
+///   [`SingleWrite`] for the 768-bit struct, where it is about 40% faster.
+///
+/// Note that the plan is part of the hash: the same value hashes differently with different plans.
+///
+/// This trait cannot be implemented outside of this crate.
+///
+/// # Examples
+///
+/// ```
+/// use derive_hash_fast::{derive_hash_fast_fields, U64Only};
+///
+/// #[derive(Eq, PartialEq)]
+/// struct Key {
+///     id: u64,
+///     shard: u32,
+/// }
+///
+/// // two `write_u64` calls instead of a single padded `write_u128`
+/// derive_hash_fast_fields!(plan = U64Only, Key { id, shard });
+/// ```
+pub trait WritePlan {
+    #[doc(hidden)]
+    fn write<O: ByteOrder>(bytes: &[u8], state: &mut impl Hasher);
+
+    /// Same as [`hash_fixed_bytes`](crate::hash_fixed_bytes), but with this plan.
+    #[inline]
+    fn hash_fixed_bytes<const N: usize>(bytes: &[u8; N], state: &mut impl Hasher) {
+        Self::write::<NativeEndian>(bytes, state)
+    }
+
+    /// Same as [`hash_fixed_bytes_portable`](crate::hash_fixed_bytes_portable), but with this plan.
+    #[inline]
+    fn hash_fixed_bytes_portable<const N: usize>(bytes: &[u8; N], state: &mut impl Hasher) {
+        Self::write::<LittleEndian>(bytes, state)
+    }
+}
+
+/// The default plan: a single write of the next supported width for values
+/// of up to 16 bytes, `write_u128` chunks with a `u64` or `u128` tail for larger ones.
+///
+/// See [`hash_fixed_bytes`](crate::hash_fixed_bytes) for the exact sequence.
+pub struct Optimal;
+
+impl WritePlan for Optimal {
+    #[inline(always)]
+    fn write<O: ByteOrder>(bytes: &[u8], state: &mut impl Hasher) {
+        crate::write_bytes_of_constant_size::<O>(bytes, state)
+    }
+}
+
+/// Only ever calls `write_u64`, with values of any size split into 8-byte chunks
+/// and the last one zero-padded.
+///
+/// Suits hashers without a specialized `write_u128`, which the `Hasher` trait
+/// implements in terms of the slow variable-length `write` by default.
+pub struct U64Only;
+
+impl WritePlan for U64Only {
+    #[inline(always)]
+    fn write<O: ByteOrder>(bytes: &[u8], state: &mut impl Hasher) {
+        const SIZEOF_U64: usize = core::mem::size_of::<u64>();
+        let chunks_iter = bytes.chunks_exact(SIZEOF_U64);
+        let remainder = chunks_iter.remainder();
+        for chunk in chunks_iter {
+            state.write_u64(O::u64(chunk.try_into().unwrap()))
+        }
+        if !remainder.is_empty() {
+            state.write_u64(pad_to_u64::<O>(remainder))
+        }
+    }
+}
+
+/// Same as [`Optimal`] for values of up to 8 bytes, but anything larger is only
+/// written with `write_u128`, including the zero-padded tail.
+///
+/// Suits hashers that mix a `u128` as cheaply as a `u64`.
+pub struct U128Preferred;
+
+impl WritePlan for U128Preferred {
+    #[inline(always)]
+    fn write<O: ByteOrder>(bytes: &[u8], state: &mut impl Hasher) {
+        const SIZEOF_U128: usize = core::mem::size_of::<u128>();
+        if bytes.len() <= core::mem::size_of::<u64>() {
+            return crate::write_bytes_of_constant_size::<O>(bytes, state);
+        }
+        let chunks_iter = bytes.chunks_exact(SIZEOF_U128);
+        let remainder = chunks_iter.remainder();
+        for chunk in chunks_iter {
+            state.write_u128(O::u128(chunk.try_into().unwrap()))
+        }
+        if !remainder.is_empty() {
+            state.write_u128(pad_to_u128::<O>(remainder))
+        }
+    }
+}
+
+/// The same sequence of writes as [`Optimal`], but the bytes that don't fill a whole integer
+/// are loaded with two overlapping reads instead of being copied into a zeroed buffer.
+///
+/// E.g. the 10 bytes of a value are loaded as `u64`s from offsets 0 and 2 and combined
+/// into a single `u128`, and the last 4 bytes of a 20-byte value are loaded as a `u64`
+/// together with the 4 bytes before them. Every byte still ends up in the hash,
+/// and because the size is fixed, different values still produce different integers.
+/// This is the trick fast byte hashers use for their tails.
+///
+/// It saves the zeroing and copying, but a load that straddles fields written separately
+/// just before hashing cannot be forwarded from the CPU's store buffer and has to wait for the stores.
+/// In the `Tail strategy` benchmarks, which build the value right before hashing it,
+/// this makes it several times slower than [`Optimal`] with the fast hashers.
+/// It can come out ahead for values that have been sitting in memory, such as keys already in a table,
+/// so measure with your own workload before selecting it.
+pub struct OverlappingTail;
+
+impl WritePlan for OverlappingTail {
+    #[inline(always)]
+    fn write<O: ByteOrder>(bytes: &[u8], state: &mut impl Hasher) {
+        const SIZEOF_U128: usize = core::mem::size_of::<u128>();
+        match bytes.len() {
+            3 => state.write_u32(overlapping_u32::<O>(bytes)),
+            5..=7 => state.write_u64(overlapping_u64::<O>(bytes)),
+            9..=15 => state.write_u128(overlapping_u128::<O>(bytes)),
+            17.. => {
+                let chunks_iter = bytes.chunks_exact(SIZEOF_U128);
+                let remainder = chunks_iter.remainder().len();
+                for chunk in chunks_iter {
+                    state.write_u128(O::u128(chunk.try_into().unwrap()))
+                }
+                // re-read some of the bytes of the last full chunk along with the remainder
+                let len = bytes.len();
+                match remainder {
+                    0 => (),
+                    1..=8 => state.write_u64(O::u64(bytes[len - 8..].try_into().unwrap())),
+                    _ => state.write_u128(O::u128(bytes[len - 16..].try_into().unwrap())),
+                }
+            }
+            // whole integers, nothing to pad
+            _ => crate::write_bytes_of_constant_size::<O>(bytes, state),
+        }
+    }
+}
+
+/// Loads 2 to 4 bytes as two overlapping `u16`s.
+#[inline(always)]
+pub(crate) fn overlapping_u32<O: ByteOrder>(bytes: &[u8]) -> u32 {
+    let lo = O::u16(bytes[..2].try_into().unwrap());
+    let hi = O::u16(bytes[bytes.len() - 2..].try_into().unwrap());
+    u32::from(lo) | u32::from(hi) << 16
+}
+
+/// Loads 4 to 8 bytes as two overlapping `u32`s.
+#[inline(always)]
+pub(crate) fn overlapping_u64<O: ByteOrder>(bytes: &[u8]) -> u64 {
+    let lo = O::u32(bytes[..4].try_into().unwrap());
+    let hi = O::u32(bytes[bytes.len() - 4..].try_into().unwrap());
+    u64::from(lo) | u64::from(hi) << 32
+}
+
+/// Loads 8 to 16 bytes as two overlapping `u64`s.
+#[inline(always)]
+pub(crate) fn overlapping_u128<O: ByteOrder>(bytes: &[u8]) -> u128 {
+    let lo = O::u64(bytes[..8].try_into().unwrap());
+    let hi = O::u64(bytes[bytes.len() - 8..].try_into().unwrap());
+    u128::from(lo) | u128::from(hi) << 64
+}
+
+/// Passes all the bytes to a single `Hasher::write` call.
+///
+/// Suits hashers with a high per-call overhead, and hashers with a fast bulk path for large types.
+pub struct SingleWrite;
+
+impl WritePlan for SingleWrite {
+    #[inline(always)]
+    fn write<O: ByteOrder>(bytes: &[u8], state: &mut impl Hasher) {
+        if !bytes.is_empty() {
+            state.write(bytes)
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
+
+use crate::WriteWidth;
+
+/// A call to a [`Hasher`] recorded by [`RecordingHasher`], with the value that was passed.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RecordedWrite {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    Usize(usize),
+    /// The variable-length `Hasher::write`
+    Bytes(Vec<u8>),
+}
+
+impl RecordedWrite {
+    /// Returns the width of the call.
+    pub fn width(&self) -> WriteWidth {
+        match self {
+            RecordedWrite::U8(_) => WriteWidth::U8,
+            RecordedWrite::U16(_) => WriteWidth::U16,
+            RecordedWrite::U32(_) => WriteWidth::U32,
+            RecordedWrite::U64(_) => WriteWidth::U64,
+            RecordedWrite::U128(_) => WriteWidth::U128,
+            RecordedWrite::Usize(_) => WriteWidth::Usize,
+            RecordedWrite::Bytes(_) => WriteWidth::Bytes,
+        }
+    }
+}
+
+/// A [`Hasher`] that records every call made to it instead of hashing anything.
+///
+/// Shows the sequence of `write_*` calls a `Hash` implementation makes, e.g. to check that
+/// a type hashed by the macros gets the expected writes and never falls back to the
+/// variable-length `write`. See [`assert_write_plan!`](crate::assert_write_plan)
+/// to check the sequence in a test.
+///
+/// `finish` returns the number of recorded calls, so that it can be used where a `Hasher`
+/// is expected, but it is not a hash function.
+///
+/// Requires the `testing` feature.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "testing")] {
+/// use derive_hash_fast::{derive_hash_fast_bytemuck, RecordedWrite, RecordingHasher};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, bytemuck::NoUninit)]
+/// struct Pair {
+///     a: u32,
+///     b: u16,
+///     c: u16,
+/// }
+///
+/// derive_hash_fast_bytemuck!(Pair);
+///
+/// let pair = Pair { a: 1, b: 2, c: 3 };
+/// let recorder = RecordingHasher::record(&pair);
+/// assert_eq!(recorder.writes(), [RecordedWrite::U64(u64::from_ne_bytes(bytemuck::cast(pair)))]);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RecordingHasher {
+    writes: Vec<RecordedWrite>,
+}
+
+impl RecordingHasher {
+    /// Creates a hasher with no calls recorded.
+    pub const fn new() -> Self {
+        Self { writes: Vec::new() }
+    }
+
+    /// Records the calls `Hash::hash` makes for `value`.
+    pub fn record<T: Hash + ?Sized>(value: &T) -> Self {
+        let mut recorder = Self::new();
+        value.hash(&mut recorder);
+        recorder
+    }
+
+    /// Records the calls `Hash::hash_slice` makes for `data`.
+    ///
+    /// Hashing a slice with `Hash::hash` also writes its length first, this omits it.
+    pub fn record_slice<T: Hash>(data: &[T]) -> Self {
+        let mut recorder = Self::new();
+        T::hash_slice(data, &mut recorder);
+        recorder
+    }
+
+    /// Returns the recorded calls, in the order they were made.
+    pub fn writes(&self) -> &[RecordedWrite] {
+        &self.writes
+    }
+
+    /// Returns the widths of the recorded calls, in the order they were made.
+    pub fn widths(&self) -> Vec<WriteWidth> {
+        self.writes.iter().map(RecordedWrite::width).collect()
+    }
+
+    /// Returns the recorded calls.
+    pub fn into_writes(self) -> Vec<RecordedWrite> {
+        self.writes
+    }
+}
+
+impl Hasher for RecordingHasher {
+    fn finish(&self) -> u64 {
+        self.writes.len() as u64
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.writes.push(RecordedWrite::Bytes(bytes.to_vec()))
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.writes.push(RecordedWrite::U8(i))
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.writes.push(RecordedWrite::U16(i))
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.writes.push(RecordedWrite::U32(i))
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.writes.push(RecordedWrite::U64(i))
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.writes.push(RecordedWrite::U128(i))
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.writes.push(RecordedWrite::Usize(i))
+    }
+}
+
+/// Asserts that hashing a value makes exactly the given sequence of `write_*` calls.
+///
+/// Locks down the write plan of a type in a unit test, so that a change in its size
+/// or a fallback to the variable-length `write` path is caught. The widths are the variants of
+/// [`WriteWidth`](crate::WriteWidth): `U8`, `U16`, `U32`, `U64`, `U128`, `Usize` and `Bytes`.
+///
+/// - `assert_write_plan!(MyType, [...])` hashes `MyType::default()`.
+/// - `assert_write_plan!(MyType = value, [...])` hashes `value`, for types without `Default`.
+/// - `assert_write_plan!([MyType] = slice, [...])` records `Hash::hash_slice`, without the length prefix.
+///
+/// On failure, the message lists the recorded calls along with their values.
+/// Requires the `testing` feature.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "testing")] {
+/// use derive_hash_fast::{assert_write_plan, derive_hash_fast_bytemuck};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, Default, bytemuck::NoUninit)]
+/// struct Header {
+///     id: u64,
+///     len: u32,
+///     kind: u32,
+///     checksum: u64,
+/// }
+///
+/// derive_hash_fast_bytemuck!(Header);
+///
+/// // 24 bytes: a full `u128` and a `u64` tail
+/// assert_write_plan!(Header, [U128, U64]);
+/// // slices longer than 16 bytes are passed to the hasher in one variable-length write
+/// assert_write_plan!([Header] = [Header::default(); 2], [Bytes]);
+/// # }
+/// ```
+#[cfg(any(test, feature = "testing"))]
+#[macro_export]
+macro_rules! assert_write_plan {
+    (@check $recorder:expr, [$($width:ident),*]) => {{
+        let recorder = $recorder;
+        let expected: &[$crate::WriteWidth] = &[$($crate::WriteWidth::$width),*];
+        assert!(
+            recorder.widths() == expected,
+            "unexpected writes: expected [{}], got {:?}",
+            stringify!($($width),*),
+            recorder.writes(),
+        );
+    }};
+    ([$T:ty] = $value:expr, [$($width:ident),* $(,)?]) => {{
+        let data: &[$T] = &$value;
+        $crate::assert_write_plan!(@check $crate::RecordingHasher::record_slice(data), [$($width),*])
+    }};
+    ($T:ty = $value:expr, [$($width:ident),* $(,)?]) => {{
+        let value: $T = $value;
+        $crate::assert_write_plan!(@check $crate::RecordingHasher::record(&value), [$($width),*])
+    }};
+    ($T:ty, [$($width:ident),* $(,)?]) => {
+        $crate::assert_write_plan!($T = <$T as ::core::default::Default>::default(), [$($width),*])
+    };
+}
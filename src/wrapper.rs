@@ -0,0 +1,210 @@
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+
+use crate::{AsHashBytes, Optimal};
+
+/// Views `zerocopy::IntoBytes + zerocopy::Immutable` types as bytes in [`HashAsBytes`]. Requires the `zerocopy` feature.
+#[cfg(feature = "zerocopy")]
+#[derive(Clone, Copy, Debug)]
+pub enum Zerocopy {}
+
+// SAFETY: `zerocopy::IntoBytes` guarantees that `T` has no padding, the bytes of a slice
+// are the bytes of its elements, and `HashAsBytes` is `repr(transparent)` over `T`.
+// `HashAsBytes` compares the same bytes, so equal values have equal bytes
+#[cfg(feature = "zerocopy")]
+unsafe impl<T: zerocopy::IntoBytes + zerocopy::Immutable> AsHashBytes for HashAsBytes<T, Zerocopy> {
+    const SIZE: usize = core::mem::size_of::<T>();
+
+    #[inline]
+    fn hash_bytes(&self) -> &[u8] {
+        zerocopy::IntoBytes::as_bytes(&self.value)
+    }
+
+    #[inline]
+    fn slice_bytes(s: &[Self]) -> &[u8] {
+        zerocopy::IntoBytes::as_bytes(Self::as_inner_slice(s))
+    }
+}
+
+/// Views `bytemuck::NoUninit` types as bytes in [`HashAsBytes`]. Requires the `bytemuck` feature.
+#[cfg(feature = "bytemuck")]
+#[derive(Clone, Copy, Debug)]
+pub enum Bytemuck {}
+
+// SAFETY: `bytemuck::NoUninit` guarantees that `T` has no padding, the bytes of a slice
+// are the bytes of its elements, and `HashAsBytes` is `repr(transparent)` over `T`.
+// `HashAsBytes` compares the same bytes, so equal values have equal bytes
+#[cfg(feature = "bytemuck")]
+unsafe impl<T: bytemuck::NoUninit> AsHashBytes for HashAsBytes<T, Bytemuck> {
+    const SIZE: usize = core::mem::size_of::<T>();
+
+    #[inline]
+    fn hash_bytes(&self) -> &[u8] {
+        bytemuck::bytes_of(&self.value)
+    }
+
+    #[inline]
+    fn slice_bytes(s: &[Self]) -> &[u8] {
+        bytemuck::cast_slice(Self::as_inner_slice(s))
+    }
+}
+
+/// A wrapper that hashes and compares a value by its bytes, for types from other crates.
+///
+/// The orphan rule doesn't allow invoking [`derive_hash_fast_zerocopy!`](crate::derive_hash_fast_zerocopy)
+/// or [`derive_hash_fast_bytemuck!`](crate::derive_hash_fast_bytemuck) on a type defined in another crate.
+/// For any `T` that implements the traits of the respective crate, `HashAsBytes<T, Zerocopy>` and
+/// `HashAsBytes<T, Bytemuck>` implement [`AsHashBytes`] through those traits, `Hash` the same way
+/// those macros do, and `Eq` by comparing the same bytes.
+/// That requires the `zerocopy` or `bytemuck` feature of this crate.
+///
+/// Since the bytes are compared, `-0.0` is not equal to `0.0` and a NaN is equal to itself,
+/// unlike with `T`'s own `PartialEq`.
+///
+/// It dereferences to `T`. It does not implement `Borrow<T>`, because `T`'s own `Hash`, if any,
+/// differs from the wrapper's, and map lookups through it would never find the key.
+/// Use [`HashAsBytes::from_ref`] to look up keys without copying.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "zerocopy")] {
+/// use derive_hash_fast::{HashAsBytes, Zerocopy};
+/// use std::collections::HashMap;
+///
+/// // stands in for a type from another crate, e.g. a vector from a math library
+/// #[derive(zerocopy::Immutable, zerocopy::IntoBytes)]
+/// #[repr(C)]
+/// struct Vec3 {
+///     x: f32,
+///     y: f32,
+///     z: f32,
+/// }
+///
+/// let mut names = HashMap::new();
+/// names.insert(HashAsBytes::<_, Zerocopy>::new(Vec3 { x: 1.0, y: 2.0, z: 3.0 }), "origin");
+/// let key = Vec3 { x: 1.0, y: 2.0, z: 3.0 };
+/// assert_eq!(names.get(HashAsBytes::from_ref(&key)), Some(&"origin"));
+/// # }
+/// ```
+#[repr(transparent)]
+pub struct HashAsBytes<T, B> {
+    value: T,
+    backend: PhantomData<B>,
+}
+
+impl<T, B> HashAsBytes<T, B> {
+    /// Wraps a value.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Self { value, backend: PhantomData }
+    }
+
+    /// Returns the wrapped value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Views a reference to a value as a reference to a `HashAsBytes`, without copying it.
+    #[inline]
+    pub const fn from_ref(value: &T) -> &Self {
+        // SAFETY: `HashAsBytes` is `repr(transparent)` over `T`
+        unsafe { &*(value as *const T).cast::<Self>() }
+    }
+
+    /// Views a slice of values as a slice of `HashAsBytes`, without copying it.
+    #[inline]
+    pub const fn from_slice(values: &[T]) -> &[Self] {
+        // SAFETY: `HashAsBytes` is `repr(transparent)` over `T`
+        unsafe { core::slice::from_raw_parts(values.as_ptr().cast::<Self>(), values.len()) }
+    }
+
+    /// Views a slice of `HashAsBytes` as a slice of the wrapped values, without copying it.
+    #[inline]
+    pub const fn as_inner_slice(s: &[Self]) -> &[T] {
+        // SAFETY: `HashAsBytes` is `repr(transparent)` over `T`
+        unsafe { core::slice::from_raw_parts(s.as_ptr().cast::<T>(), s.len()) }
+    }
+}
+
+impl<T, B> Hash for HashAsBytes<T, B>
+where
+    Self: AsHashBytes,
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        crate::hash_as_bytes::<Optimal, Self>(self, state)
+    }
+
+    #[inline]
+    fn hash_slice<H: Hasher>(data: &[Self], state: &mut H) {
+        crate::hash_slice_as_bytes(data, state)
+    }
+}
+
+impl<T, B> PartialEq for HashAsBytes<T, B>
+where
+    Self: AsHashBytes,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        crate::bytes_eq_for::<Self>(self.hash_bytes(), other.hash_bytes())
+    }
+}
+
+impl<T, B> Eq for HashAsBytes<T, B> where Self: AsHashBytes {}
+
+impl<T: Clone, B> Clone for HashAsBytes<T, B> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.value.clone())
+    }
+}
+
+impl<T: Copy, B> Copy for HashAsBytes<T, B> {}
+
+impl<T: fmt::Debug, B> fmt::Debug for HashAsBytes<T, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("HashAsBytes").field(&self.value).finish()
+    }
+}
+
+impl<T: Default, B> Default for HashAsBytes<T, B> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T, B> Deref for HashAsBytes<T, B> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T, B> DerefMut for HashAsBytes<T, B> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T, B> From<T> for HashAsBytes<T, B> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T, B> AsRef<T> for HashAsBytes<T, B> {
+    #[inline]
+    fn as_ref(&self) -> &T {
+        &self.value
+    }
+}